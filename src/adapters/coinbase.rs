@@ -0,0 +1,65 @@
+//! Messages of the Coinbase (formerly GDAX) `ticker` channel, such as:
+//!
+//! ```text
+//! {"type":"ticker","product_id":"BTC-USD","price":"1000.5","best_bid":"1000.0",
+//!  "best_ask":"1001.0","time":"2017-11-01T09:42:23.125Z", ...}
+//! ```
+
+use crate::{
+    adapters::{decimal, field, AdapterError},
+    exchange::{Currency, ExchangeType},
+    input::PriceUpdate,
+    utils::time_utils::parse_timestamp,
+};
+use serde_json::Value;
+
+/// Convert a message of the `ticker` channel into the price update of its
+/// product, timestamped with the time of the ticker. The price of the ticker
+/// is the last trade of the update.
+///
+/// Other messages of the feed are converted into no price update.
+pub fn parse_ticker(message: &str) -> Result<Option<PriceUpdate>, AdapterError> {
+    let message: Value = serde_json::from_str(message)?;
+    if message.get("type").and_then(Value::as_str) != Some("ticker") {
+        return Ok(None);
+    }
+
+    let product = field(&message, "product_id")?
+        .as_str()
+        .ok_or(AdapterError::MissingField("product_id"))?;
+    let (base, quote) = parse_product(product)?;
+
+    let time = field(&message, "time")?;
+    let timestamp = time
+        .as_str()
+        .and_then(|time| parse_timestamp(time).ok())
+        .ok_or_else(|| AdapterError::InvalidField {
+            field: "time",
+            value: time.to_string(),
+        })?;
+
+    let update = PriceUpdate::from_bid_ask(
+        timestamp,
+        ExchangeType::GDAX,
+        base,
+        quote,
+        decimal(field(&message, "best_bid")?, "best_bid")?,
+        decimal(field(&message, "best_ask")?, "best_ask")?,
+    );
+
+    match message.get("price") {
+        Some(price) => Ok(Some(update.with_last(decimal(price, "price")?))),
+        None => Ok(Some(update)),
+    }
+}
+
+/// Parse a product in the form of `<base>-<quote>`.
+fn parse_product(product: &str) -> Result<(Currency, Currency), AdapterError> {
+    let unknown = || AdapterError::UnknownPair(product.to_string());
+    let (base, quote) = product.split_once('-').ok_or_else(unknown)?;
+
+    Ok((
+        base.parse().map_err(|_| unknown())?,
+        quote.parse().map_err(|_| unknown())?,
+    ))
+}
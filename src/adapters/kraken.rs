@@ -0,0 +1,151 @@
+//! Messages of the Kraken `ticker` channel, such as:
+//!
+//! ```text
+//! [340,{"a":["1001.00000",1,"1.000"],"b":["1000.00000",2,"2.000"], ...},"ticker","XBT/USD"]
+//! ```
+//!
+//! and responses of its `Ticker` REST endpoint, such as:
+//!
+//! ```text
+//! {"error":[],"result":{"XXBTZUSD":{"a":["1001.00000","1","1.000"],"b":["1000.00000","2","2.000"], ...}}}
+//! ```
+//!
+//! Kraken names bitcoin `XBT`, and prefixes its older asset codes with `X` for
+//! cryptocurrencies or `Z` for fiat currencies, as in `XXBTZUSD`.
+
+use crate::{
+    adapters::{decimal, field, AdapterError},
+    exchange::{Currency, ExchangeType},
+    input::PriceUpdate,
+};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+/// Asset codes of Kraken which differ from the usual ticker symbols.
+const ASSET_ALIASES: &[(&str, &str)] = &[
+    ("XBT", "BTC"),
+    ("XXBT", "BTC"),
+    ("XDG", "DOGE"),
+    ("XXDG", "DOGE"),
+];
+
+/// Convert a message of the `ticker` channel, or a response of the `Ticker`
+/// endpoint, into the price updates of its pairs.
+///
+/// Kraken tickers carry no timestamp, so the updates are timestamped with the
+/// time the message was received. Other messages of the feed, such as
+/// heartbeats, are converted into no price update.
+pub fn parse_ticker(
+    message: &str,
+    received: DateTime<Utc>,
+) -> Result<Vec<PriceUpdate>, AdapterError> {
+    let message: Value = serde_json::from_str(message)?;
+
+    match &message {
+        // `[<channel id>, <ticker>, "ticker", <pair>]`
+        Value::Array(fields) => match fields.as_slice() {
+            [_, ticker, channel, pair] if channel.as_str() == Some("ticker") => {
+                let pair = pair.as_str().ok_or(AdapterError::MissingField("pair"))?;
+                Ok(vec![parse_pair_ticker(ticker, pair, received)?])
+            }
+            _ => Ok(vec![]),
+        },
+        Value::Object(_) if message.get("result").is_some() || message.get("error").is_some() => {
+            parse_response(&message, received)
+        }
+        _ => Ok(vec![]),
+    }
+}
+
+/// Convert a response of the `Ticker` endpoint, ordered by pair.
+fn parse_response(
+    response: &Value,
+    received: DateTime<Utc>,
+) -> Result<Vec<PriceUpdate>, AdapterError> {
+    let errors = response.get("error").and_then(Value::as_array);
+    if let Some(errors) = errors.filter(|errors| !errors.is_empty()) {
+        let errors: Vec<String> = errors.iter().map(|error| error.to_string()).collect();
+        return Err(AdapterError::ExchangeError(errors.join(", ")));
+    }
+
+    let result = field(response, "result")?
+        .as_object()
+        .ok_or(AdapterError::MissingField("result"))?;
+
+    let mut pairs: Vec<_> = result.iter().collect();
+    pairs.sort_by_key(|(pair, _)| pair.as_str());
+    pairs
+        .into_iter()
+        .map(|(pair, ticker)| parse_pair_ticker(ticker, pair, received))
+        .collect()
+}
+
+/// Convert the ticker of a single pair, from its best bid (`b`) and ask (`a`),
+/// along with its last trade (`c`) if available.
+fn parse_pair_ticker(
+    ticker: &Value,
+    pair: &str,
+    received: DateTime<Utc>,
+) -> Result<PriceUpdate, AdapterError> {
+    let (base, quote) = parse_pair(pair)?;
+    let best = |name: &'static str| {
+        let price = field(ticker, name)?
+            .get(0)
+            .ok_or(AdapterError::MissingField(name))?;
+        decimal(price, name)
+    };
+
+    let update = PriceUpdate::from_bid_ask(
+        received,
+        ExchangeType::KRAKEN,
+        base,
+        quote,
+        best("b")?,
+        best("a")?,
+    );
+
+    match ticker.get("c") {
+        Some(_) => Ok(update.with_last(best("c")?)),
+        None => Ok(update),
+    }
+}
+
+/// Parse a pair named either `XBT/USD`, `XXBTZUSD` or `XBTUSD`.
+///
+/// Pairs without a separator are only split around registered currencies.
+pub fn parse_pair(pair: &str) -> Result<(Currency, Currency), AdapterError> {
+    let unknown = || AdapterError::UnknownPair(pair.to_string());
+
+    if let Some((base, quote)) = pair.split_once('/') {
+        return Ok((
+            asset_symbol(base).parse().map_err(|_| unknown())?,
+            asset_symbol(quote).parse().map_err(|_| unknown())?,
+        ));
+    }
+
+    [4, 3]
+        .iter()
+        .filter(|length| pair.len() > **length && pair.is_char_boundary(pair.len() - **length))
+        .find_map(|length| {
+            let (base, quote) = pair.split_at(pair.len() - length);
+            Some((
+                Currency::lookup(asset_symbol(base))?,
+                Currency::lookup(asset_symbol(quote))?,
+            ))
+        })
+        .ok_or_else(unknown)
+}
+
+/// Gets the usual ticker symbol of a Kraken asset code.
+fn asset_symbol(code: &str) -> &str {
+    if let Some((_, symbol)) = ASSET_ALIASES.iter().find(|(alias, _)| *alias == code) {
+        return symbol;
+    }
+
+    let prefixed = code.len() == 4 && (code.starts_with('X') || code.starts_with('Z'));
+    if prefixed && Currency::lookup(code).is_none() {
+        &code[1..]
+    } else {
+        code
+    }
+}
@@ -0,0 +1,81 @@
+//! Adapters converting the native ticker messages of exchanges into price updates.
+//!
+//! - `coinbase`: messages of the Coinbase (formerly GDAX) `ticker` channel.
+//! - `kraken`: messages of the Kraken `ticker` channel, and responses of its
+//!   `Ticker` REST endpoint.
+//!
+//! Messages which aren't tickers, such as heartbeats or subscription
+//! confirmations, are converted into no price update at all.
+
+pub mod coinbase;
+pub mod kraken;
+
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    str::FromStr,
+};
+
+/// Errors that can occur while converting the message of an exchange.
+#[derive(Debug)]
+pub enum AdapterError {
+    InvalidJson(serde_json::Error),
+    MissingField(&'static str),
+    InvalidField { field: &'static str, value: String },
+    UnknownPair(String),
+    ExchangeError(String),
+}
+
+/// Gets a field of a message which must be present.
+fn field<'a>(message: &'a Value, name: &'static str) -> Result<&'a Value, AdapterError> {
+    message.get(name).ok_or(AdapterError::MissingField(name))
+}
+
+/// Parse a decimal sent either as a string, or as a number.
+fn decimal(value: &Value, name: &'static str) -> Result<Decimal, AdapterError> {
+    let text = match value {
+        Value::String(text) => text.clone(),
+        Value::Number(number) => number.to_string(),
+        _ => String::new(),
+    };
+
+    Decimal::from_str(&text)
+        .ok()
+        .filter(|decimal| *decimal > Decimal::from(0))
+        .ok_or_else(|| AdapterError::InvalidField {
+            field: name,
+            value: value.to_string(),
+        })
+}
+
+impl Display for AdapterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AdapterError::InvalidJson(_) => write!(f, "message is not valid JSON"),
+            AdapterError::MissingField(field) => write!(f, "missing field `{}`", field),
+            AdapterError::InvalidField { field, value } => {
+                write!(f, "invalid value {} of field `{}`", value, field)
+            }
+            AdapterError::UnknownPair(pair) => write!(f, "unknown pair `{}`", pair),
+            AdapterError::ExchangeError(reason) => write!(f, "exchange error: {}", reason),
+        }
+    }
+}
+
+impl Error for AdapterError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AdapterError::InvalidJson(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for AdapterError {
+    /// Convert from `serde_json::Error` to `AdapterError`.
+    fn from(error: serde_json::Error) -> Self {
+        AdapterError::InvalidJson(error)
+    }
+}
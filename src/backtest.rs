@@ -0,0 +1,324 @@
+//! Replays a historical file of price updates along with timed exchange rate
+//! requests, to evaluate how requests would have been routed.
+//!
+//! Every line is either a price update, or an exchange rate request optionally
+//! prefixed by the timestamp it is made at:
+//!
+//! ```text
+//! 2017-11-01T09:42:23+00:00 KRAKEN BTC USD 1000.0 0.0009
+//! 2017-11-01T09:45:00+00:00 EXCHANGE_RATE_REQUEST KRAKEN USD KRAKEN BTC
+//! EXCHANGE_RATE_REQUEST GDAX BTC GDAX USD
+//! ```
+//!
+//! Lines are replayed in timestamp order, and a request without a timestamp is
+//! made right after the line before it. Every request is answered with the best
+//! rate known at its time, and followed through the rest of the file to see how
+//! its rate evolved.
+
+use crate::{
+    exchange::{BestRate, ExchangeVertex, ExchangeVertexPair, RateGraph, RateGraphError},
+    input::{line::timestamp, ExchangeRateRequest, LineParseError, PriceUpdate},
+    utils::time_utils::TIMESTAMP_FORMAT,
+};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    io::{self, BufRead, Write},
+    str::FromStr,
+};
+
+/// Prefix that distinguishes an exchange rate request from a price update.
+const EXCHANGE_RATE_REQUEST: &str = "EXCHANGE_RATE_REQUEST";
+
+/// Header of the CSV summary of a backtest.
+const CSV_HEADER: &str =
+    "line,timestamp,from,to,rate,path,final_rate,min_rate,max_rate,changes,error";
+
+/// Errors that can occur while running a backtest.
+#[derive(Debug)]
+pub enum BacktestError {
+    Io(io::Error),
+    InvalidLine(usize, LineParseError),
+}
+
+/// A single line of a backtest file.
+enum Event {
+    Update(PriceUpdate),
+    Request(ExchangeRateRequest),
+}
+
+/// A line of a backtest file, along with its line number and the time it happens at.
+type TimedEvent = (usize, Option<DateTime<Utc>>, Event);
+
+/// The outcome of an exchange rate request made during a backtest.
+#[derive(Debug)]
+pub struct RequestOutcome {
+    line: usize,
+    timestamp: Option<DateTime<Utc>>,
+    request: ExchangeRateRequest,
+    best_rate: Result<BestRate, RateGraphError>,
+    evolution: Vec<(DateTime<Utc>, Option<Decimal>)>,
+}
+
+impl RequestOutcome {
+    /// Gets the line number of the request in the backtest file.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Gets the time the request was made at, which is the timestamp of the
+    /// latest line replayed before it.
+    pub fn timestamp(&self) -> Option<&DateTime<Utc>> {
+        self.timestamp.as_ref()
+    }
+
+    /// Gets the exchange rate request.
+    pub fn request(&self) -> &ExchangeRateRequest {
+        &self.request
+    }
+
+    /// Gets the best rate quoted for the request, or why there wasn't any.
+    pub fn best_rate(&self) -> Result<&BestRate, &RateGraphError> {
+        self.best_rate.as_ref()
+    }
+
+    /// Gets every change of the best rate of the request after it was made,
+    /// with the timestamp of the price update which caused it. A rate of `None`
+    /// means the request couldn't be answered anymore.
+    pub fn evolution(&self) -> &[(DateTime<Utc>, Option<Decimal>)] {
+        &self.evolution
+    }
+
+    /// Gets the best rate of the request at the end of the backtest.
+    pub fn final_rate(&self) -> Option<Decimal> {
+        match self.evolution.last() {
+            Some((_, rate)) => *rate,
+            None => self.rate(),
+        }
+    }
+
+    /// Gets the lowest best rate of the request since it was made.
+    pub fn min_rate(&self) -> Option<Decimal> {
+        self.rates().min()
+    }
+
+    /// Gets the highest best rate of the request since it was made.
+    pub fn max_rate(&self) -> Option<Decimal> {
+        self.rates().max()
+    }
+
+    /// Gets the best rate quoted when the request was made.
+    fn rate(&self) -> Option<Decimal> {
+        self.best_rate.as_ref().ok().map(BestRate::rate)
+    }
+
+    /// Gets every best rate the request had since it was made.
+    fn rates(&self) -> impl Iterator<Item = Decimal> + '_ {
+        self.rate()
+            .into_iter()
+            .chain(self.evolution.iter().filter_map(|(_, rate)| *rate))
+    }
+
+    /// Follow the best rate of the request after a price update.
+    fn track(&mut self, rate_graph: &RateGraph, timestamp: DateTime<Utc>) {
+        let rate = rate_graph
+            .full_path(&self.request)
+            .ok()
+            .map(|rate| rate.rate());
+
+        if rate != self.final_rate() {
+            self.evolution.push((timestamp, rate));
+        }
+    }
+}
+
+/// The outcome of replaying a backtest file.
+#[derive(Debug)]
+pub struct Backtest {
+    rate_graph: RateGraph,
+    outcomes: Vec<RequestOutcome>,
+}
+
+impl Backtest {
+    /// Replay a backtest file into the specified rate graph, which may already
+    /// hold fees or prices.
+    ///
+    /// Stale and invalid price updates are handled as by `RateGraph::insert`,
+    /// while a line which is neither an update nor a request fails the backtest.
+    pub fn run(reader: impl BufRead, mut rate_graph: RateGraph) -> Result<Self, BacktestError> {
+        let mut events = read_events(reader)?;
+        events.sort_by_key(|(_, timestamp, _)| *timestamp);
+
+        let mut outcomes: Vec<RequestOutcome> = vec![];
+        for (line, timestamp, event) in events {
+            match event {
+                Event::Update(update) => {
+                    let timestamp = *update.timestamp();
+                    let report = rate_graph.insert(vec![update]);
+
+                    if !report.applied().is_empty() {
+                        for outcome in outcomes.iter_mut() {
+                            outcome.track(&rate_graph, timestamp);
+                        }
+                    }
+                }
+                Event::Request(request) => outcomes.push(RequestOutcome {
+                    line,
+                    timestamp,
+                    best_rate: rate_graph.full_path(&request),
+                    request,
+                    evolution: vec![],
+                }),
+            }
+        }
+
+        Ok(Self {
+            rate_graph,
+            outcomes,
+        })
+    }
+
+    /// Gets the outcome of every request, in the order they were made.
+    pub fn outcomes(&self) -> &[RequestOutcome] {
+        &self.outcomes
+    }
+
+    /// Gets the rate graph at the end of the backtest.
+    pub fn rate_graph(&self) -> &RateGraph {
+        &self.rate_graph
+    }
+
+    /// Write a CSV summary of the backtest, with a row per request.
+    ///
+    /// Vertices are written as `<exchange>:<currency>`, and the path of the best
+    /// rate as its vertices separated by spaces.
+    pub fn write_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "{}", CSV_HEADER)?;
+
+        for outcome in self.outcomes.iter() {
+            let (from, to) = ExchangeVertexPair::from(outcome.request());
+            let (rate, path, error) = match outcome.best_rate() {
+                Ok(best_rate) => {
+                    let path = best_rate.vertices().iter().map(vertex).collect::<Vec<_>>();
+                    (best_rate.rate().to_string(), path.join(" "), String::new())
+                }
+                Err(error) => (String::new(), String::new(), error.to_string()),
+            };
+
+            let fields = [
+                outcome.line().to_string(),
+                outcome
+                    .timestamp()
+                    .map(|timestamp| timestamp.format(TIMESTAMP_FORMAT).to_string())
+                    .unwrap_or_default(),
+                vertex(&from),
+                vertex(&to),
+                rate,
+                path,
+                optional(outcome.final_rate()),
+                optional(outcome.min_rate()),
+                optional(outcome.max_rate()),
+                outcome.evolution().len().to_string(),
+                error,
+            ];
+
+            let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+            writeln!(writer, "{}", row.join(","))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Read every line of a backtest file, along with its line number and the
+/// time it happens at.
+fn read_events(reader: impl BufRead) -> Result<Vec<TimedEvent>, BacktestError> {
+    let mut events = vec![];
+    let mut last_timestamp = None;
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = index + 1;
+        let data = line.trim();
+
+        if data.is_empty() {
+            continue;
+        }
+
+        let (timestamp, event) =
+            parse_event(data).map_err(|error| BacktestError::InvalidLine(line_number, error))?;
+        let timestamp = timestamp.or(last_timestamp);
+
+        events.push((line_number, timestamp, event));
+        last_timestamp = timestamp;
+    }
+
+    Ok(events)
+}
+
+/// Parse a single line of a backtest file, along with its timestamp if any.
+fn parse_event(data: &str) -> Result<(Option<DateTime<Utc>>, Event), LineParseError> {
+    if data.starts_with(EXCHANGE_RATE_REQUEST) {
+        let request = ExchangeRateRequest::from_str(data)?;
+        return Ok((None, Event::Request(request)));
+    }
+
+    if let Some((at, rest)) = data.split_once(char::is_whitespace) {
+        if rest.trim_start().starts_with(EXCHANGE_RATE_REQUEST) {
+            let timestamp = timestamp((1, at))?;
+            let request = ExchangeRateRequest::from_str(rest)?;
+            return Ok((Some(timestamp), Event::Request(request)));
+        }
+    }
+
+    let update = PriceUpdate::from_str(data)?;
+    Ok((Some(*update.timestamp()), Event::Update(update)))
+}
+
+/// Format an exchange vertex as `<exchange>:<currency>`.
+fn vertex(vertex: &ExchangeVertex) -> String {
+    format!("{}:{}", vertex.exchange(), vertex.currency())
+}
+
+/// Format an optional rate, leaving it empty if unset.
+fn optional(rate: Option<Decimal>) -> String {
+    rate.map(|rate| rate.to_string()).unwrap_or_default()
+}
+
+/// Quote a CSV field if it contains a separator, a quote or a line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+impl Display for BacktestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BacktestError::Io(_) => write!(f, "failed to read backtest"),
+            BacktestError::InvalidLine(line, error) => {
+                write!(f, "invalid backtest line {}: {}", line, error)
+            }
+        }
+    }
+}
+
+impl Error for BacktestError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            BacktestError::Io(error) => Some(error),
+            BacktestError::InvalidLine(_, error) => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for BacktestError {
+    /// Convert from `io::Error` to `BacktestError`.
+    fn from(error: io::Error) -> Self {
+        BacktestError::Io(error)
+    }
+}
@@ -0,0 +1,63 @@
+use exchange_rate::{
+    exchange::{load_registry, PriceLog},
+    prelude::*,
+    server::Server,
+};
+use std::{env, fs::File, io, io::BufReader, path::Path, process};
+
+/// Default address the server listens on.
+const DEFAULT_ADDR: &str = "127.0.0.1:8000";
+
+/// Serves best rates over HTTP, from price updates posted to the server.
+///
+/// Options:
+/// - `--addr <addr>`: the address to listen on, defaulting to `127.0.0.1:8000`.
+/// - `--registry <file>`: registers the currencies and exchanges listed in a file.
+/// - `--log <file>`: replays a log of price updates on start, and appends every
+///   posted price update to it before applying it.
+///
+/// Unknown currencies and exchanges are never discovered from requests, as they
+/// come from the network: they must be listed in the registry instead.
+fn main() -> io::Result<()> {
+    let mut addr = DEFAULT_ADDR.to_string();
+    let mut log = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_ref() {
+            "--addr" => addr = args.next().unwrap_or_default(),
+            "--log" => log = Some(args.next().unwrap_or_default()),
+            "--registry" => {
+                let path = args.next().unwrap_or_default();
+                let file = BufReader::new(File::open(&path)?);
+
+                if let Err(error) = load_registry(file) {
+                    eprintln!("{}: {}", path, error);
+                    process::exit(1);
+                }
+            }
+            _ => {
+                eprintln!("usage: exchange-rate-server [--addr <addr>] [--registry <file>] [--log <file>]");
+                process::exit(1);
+            }
+        }
+    }
+
+    let mut rate_graph = RateGraph::default();
+    if let Some(path) = log.as_ref().filter(|path| Path::new(path).exists()) {
+        let file = BufReader::new(File::open(path)?);
+
+        if let Err(error) = rate_graph.replay(file, None) {
+            eprintln!("{}: {}", path, error);
+            process::exit(1);
+        }
+    }
+
+    let mut server = Server::bind(&addr, rate_graph)?;
+    if let Some(path) = log {
+        server = server.with_log(PriceLog::open(path)?);
+    }
+
+    eprintln!("listening on {}", server.local_addr());
+    server.run()
+}
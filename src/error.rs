@@ -0,0 +1,163 @@
+#[cfg(feature = "adapters")]
+use crate::adapters::AdapterError;
+use crate::{
+    backtest::BacktestError,
+    exchange::{
+        CurrencyParseError, ExchangeTypeParseError, LogError, RateGraphError, RegistryLoadError,
+        SnapshotError, UpdateRejection,
+    },
+    input::{CsvError, ExchangeRateRequestParseError, PriceUpdateParseError},
+};
+use std::{
+    error,
+    fmt::{self, Display},
+    io,
+};
+
+/// Any error of the crate, so callers can propagate all of them with `?`.
+///
+/// Every variant wraps the error of the operation that failed, and is
+/// displayed as that error.
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Currency(CurrencyParseError),
+    ExchangeType(ExchangeTypeParseError),
+    PriceUpdate(PriceUpdateParseError),
+    ExchangeRateRequest(ExchangeRateRequestParseError),
+    RateGraph(RateGraphError),
+    Rejection(UpdateRejection),
+    Registry(RegistryLoadError),
+    Snapshot(SnapshotError),
+    Log(LogError),
+    Backtest(BacktestError),
+    Csv(CsvError),
+    #[cfg(feature = "adapters")]
+    Adapter(AdapterError),
+}
+
+impl Error {
+    /// Gets the wrapped error.
+    fn inner(&self) -> &(dyn error::Error + 'static) {
+        match self {
+            Error::Io(error) => error,
+            Error::Currency(error) => error,
+            Error::ExchangeType(error) => error,
+            Error::PriceUpdate(error) => error,
+            Error::ExchangeRateRequest(error) => error,
+            Error::RateGraph(error) => error,
+            Error::Rejection(error) => error,
+            Error::Registry(error) => error,
+            Error::Snapshot(error) => error,
+            Error::Log(error) => error,
+            Error::Backtest(error) => error,
+            Error::Csv(error) => error,
+            #[cfg(feature = "adapters")]
+            Error::Adapter(error) => error,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(self.inner(), f)
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        self.inner().source()
+    }
+}
+
+impl From<io::Error> for Error {
+    /// Convert from `io::Error` to `Error`.
+    fn from(error: io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<CurrencyParseError> for Error {
+    /// Convert from `CurrencyParseError` to `Error`.
+    fn from(error: CurrencyParseError) -> Self {
+        Error::Currency(error)
+    }
+}
+
+impl From<ExchangeTypeParseError> for Error {
+    /// Convert from `ExchangeTypeParseError` to `Error`.
+    fn from(error: ExchangeTypeParseError) -> Self {
+        Error::ExchangeType(error)
+    }
+}
+
+impl From<PriceUpdateParseError> for Error {
+    /// Convert from `PriceUpdateParseError` to `Error`.
+    fn from(error: PriceUpdateParseError) -> Self {
+        Error::PriceUpdate(error)
+    }
+}
+
+impl From<ExchangeRateRequestParseError> for Error {
+    /// Convert from `ExchangeRateRequestParseError` to `Error`.
+    fn from(error: ExchangeRateRequestParseError) -> Self {
+        Error::ExchangeRateRequest(error)
+    }
+}
+
+impl From<RateGraphError> for Error {
+    /// Convert from `RateGraphError` to `Error`.
+    fn from(error: RateGraphError) -> Self {
+        Error::RateGraph(error)
+    }
+}
+
+impl From<UpdateRejection> for Error {
+    /// Convert from `UpdateRejection` to `Error`.
+    fn from(error: UpdateRejection) -> Self {
+        Error::Rejection(error)
+    }
+}
+
+impl From<RegistryLoadError> for Error {
+    /// Convert from `RegistryLoadError` to `Error`.
+    fn from(error: RegistryLoadError) -> Self {
+        Error::Registry(error)
+    }
+}
+
+impl From<SnapshotError> for Error {
+    /// Convert from `SnapshotError` to `Error`.
+    fn from(error: SnapshotError) -> Self {
+        Error::Snapshot(error)
+    }
+}
+
+impl From<LogError> for Error {
+    /// Convert from `LogError` to `Error`.
+    fn from(error: LogError) -> Self {
+        Error::Log(error)
+    }
+}
+
+impl From<BacktestError> for Error {
+    /// Convert from `BacktestError` to `Error`.
+    fn from(error: BacktestError) -> Self {
+        Error::Backtest(error)
+    }
+}
+
+impl From<CsvError> for Error {
+    /// Convert from `CsvError` to `Error`.
+    fn from(error: CsvError) -> Self {
+        Error::Csv(error)
+    }
+}
+
+#[cfg(feature = "adapters")]
+impl From<AdapterError> for Error {
+    /// Convert from `AdapterError` to `Error`.
+    fn from(error: AdapterError) -> Self {
+        Error::Adapter(error)
+    }
+}
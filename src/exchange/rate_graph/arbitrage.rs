@@ -0,0 +1,185 @@
+use crate::exchange::ExchangeVertex;
+use num_traits::cast::ToPrimitive;
+use petgraph::graphmap::DiGraphMap;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// Smallest improvement of a log weighted distance considered as a relaxation,
+/// so cycles whose rates multiply to exactly one aren't reported due to rounding.
+const EPSILON: f64 = 1e-12;
+
+/// A sequence of exchange vertices which ends where it starts, and whose
+/// rates multiply to a value greater than one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ArbitrageCycle {
+    vertices: Vec<ExchangeVertex>,
+    gain: Decimal,
+}
+
+impl ArbitrageCycle {
+    /// Gets the vertices of the cycle in trading order, starting from its
+    /// smallest vertex. The first vertex is not repeated at the end.
+    pub fn vertices(&self) -> &[ExchangeVertex] {
+        &self.vertices
+    }
+
+    /// Gets the factor by which an amount grows after one trip around the cycle.
+    pub fn gain(&self) -> Decimal {
+        self.gain
+    }
+
+    /// Checks if a given vertex is part of the cycle.
+    pub fn contains(&self, vertex: &ExchangeVertex) -> bool {
+        self.vertices.contains(vertex)
+    }
+}
+
+/// Find the profitable cycles of a rate graph using the Bellman Ford's
+/// algorithm.
+///
+/// Every vertex acts as a source, so cycles are found in all components of
+/// the graph. Each distinct cycle is only reported once.
+///
+/// Cycles are first searched on the negative logarithm of every rate, whose
+/// distances can't overflow however profitable a cycle is. When none is found
+/// there, every cycle has a gain too small for the distances to grow large, and
+/// cycles are searched again on the exact rates, which the best paths compare,
+/// so that a gain lost to the precision of logarithms is still found.
+pub(crate) fn arbitrage_cycles(graph: &DiGraphMap<ExchangeVertex, Decimal>) -> Vec<ArbitrageCycle> {
+    let rates: Vec<(ExchangeVertex, ExchangeVertex, Decimal)> = graph
+        .all_edges()
+        .filter(|(_, _, w)| **w > Decimal::from(0))
+        .map(|(u, v, w)| (u, v, *w))
+        .collect();
+
+    let logs: Vec<(ExchangeVertex, ExchangeVertex, f64)> = rates
+        .iter()
+        .filter_map(|&(u, v, w)| Some((u, v, -w.to_f64()?.ln())))
+        .collect();
+
+    let (predecessor, relaxed) = bellman_ford(graph, &logs, 0.0, |distance, w| distance + w);
+    let cycles = cycles_from(graph, &predecessor, relaxed);
+    if !cycles.is_empty() {
+        return cycles;
+    }
+
+    let (predecessor, relaxed) =
+        bellman_ford(graph, &rates, Decimal::from(1), |distance, w| distance * w);
+    cycles_from(graph, &predecessor, relaxed)
+}
+
+/// Run the Bellman Ford's algorithm from every vertex at once, starting at the
+/// given distance, where `extend` gets the distance through an edge.
+///
+/// Returns the predecessor of every vertex, along with the vertices still
+/// relaxed after all iterations, which lead into cycles.
+fn bellman_ford<W>(
+    graph: &DiGraphMap<ExchangeVertex, Decimal>,
+    edges: &[(ExchangeVertex, ExchangeVertex, W)],
+    start: W,
+    extend: impl Fn(W, W) -> W,
+) -> (HashMap<ExchangeVertex, ExchangeVertex>, Vec<ExchangeVertex>)
+where
+    W: Distance,
+{
+    let mut distance: HashMap<ExchangeVertex, W> = graph.nodes().map(|n| (n, start)).collect();
+    let mut predecessor: HashMap<ExchangeVertex, ExchangeVertex> = HashMap::new();
+    let mut relaxed = Vec::new();
+
+    for _ in 0..graph.node_count() {
+        relaxed.clear();
+
+        for &(u, v, w) in edges.iter() {
+            let total = extend(distance[&u], w);
+
+            if total.improves(distance[&v]) {
+                distance.insert(v, total);
+                predecessor.insert(v, u);
+                relaxed.push(v);
+            }
+        }
+
+        if relaxed.is_empty() {
+            break;
+        }
+    }
+
+    (predecessor, relaxed)
+}
+
+/// A distance of the Bellman Ford's algorithm.
+trait Distance: Copy {
+    /// Checks if the distance is better than another one.
+    fn improves(self, other: Self) -> bool;
+}
+
+impl Distance for f64 {
+    // Log weighted distances only improve by more than `EPSILON`.
+    fn improves(self, other: Self) -> bool {
+        self < other - EPSILON
+    }
+}
+
+impl Distance for Decimal {
+    // Products of rates are better the larger they are.
+    fn improves(self, other: Self) -> bool {
+        self > other
+    }
+}
+
+/// Recover the distinct profitable cycles which relaxed vertices lead into.
+fn cycles_from(
+    graph: &DiGraphMap<ExchangeVertex, Decimal>,
+    predecessor: &HashMap<ExchangeVertex, ExchangeVertex>,
+    relaxed: Vec<ExchangeVertex>,
+) -> Vec<ArbitrageCycle> {
+    let mut cycles: Vec<ArbitrageCycle> = vec![];
+
+    for vertex in relaxed {
+        if let Some(cycle) = cycle_from(graph, predecessor, vertex) {
+            if !cycles.contains(&cycle) {
+                cycles.push(cycle);
+            }
+        }
+    }
+
+    cycles
+}
+
+/// Walk back the predecessors of a vertex which was still relaxed after all
+/// Bellman Ford's iterations, to recover the cycle it leads into.
+fn cycle_from(
+    graph: &DiGraphMap<ExchangeVertex, Decimal>,
+    predecessor: &HashMap<ExchangeVertex, ExchangeVertex>,
+    mut vertex: ExchangeVertex,
+) -> Option<ArbitrageCycle> {
+    // After as many steps as there are vertices, the walk must be on the cycle.
+    for _ in 0..graph.node_count() {
+        vertex = *predecessor.get(&vertex)?;
+    }
+
+    let mut vertices = vec![vertex];
+    let mut current = *predecessor.get(&vertex)?;
+    while current != vertex {
+        vertices.push(current);
+        current = *predecessor.get(&current)?;
+    }
+
+    // Predecessors were collected backwards, so restore the trading order.
+    vertices.reverse();
+    let start = vertices.iter().enumerate().min_by_key(|(_, v)| **v)?.0;
+    vertices.rotate_left(start);
+
+    let gain = vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(u, v)| *graph.edge_weight(*u, *v).unwrap())
+        .fold(Decimal::from(1), |gain, rate| gain * rate);
+
+    if gain > Decimal::from(1) {
+        Some(ArbitrageCycle { vertices, gain })
+    } else {
+        None
+    }
+}
@@ -0,0 +1,173 @@
+use crate::{
+    exchange::{ExchangeVertex, ExchangeVertexPair},
+    input::ExchangeRateRequest,
+};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::fmt::{self, Display};
+
+/// The kind of move made by a single hop of a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HopKind {
+    /// A trade from one currency to another, within the same exchange.
+    Trade,
+
+    /// A transfer of the same currency from one exchange to another.
+    Transfer,
+}
+
+/// A single move between two adjacent exchange vertices of a path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hop {
+    source: ExchangeVertex,
+    destination: ExchangeVertex,
+    rate: Decimal,
+    fee: Decimal,
+    kind: HopKind,
+    timestamp: Option<DateTime<Utc>>,
+}
+
+/// The most optimal sequence of trades and transfers across exchanges for a
+/// given exchange rate request, along with its rate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BestRate {
+    request: ExchangeRateRequest,
+    hops: Vec<Hop>,
+    rate: Decimal,
+}
+
+impl Hop {
+    /// Create a new `Hop` from specified values. The kind of the hop is
+    /// derived from the exchanges of both vertices.
+    pub fn new(
+        source: ExchangeVertex,
+        destination: ExchangeVertex,
+        rate: Decimal,
+        fee: Decimal,
+        timestamp: Option<DateTime<Utc>>,
+    ) -> Self {
+        let kind = if source.exchange() == destination.exchange() {
+            HopKind::Trade
+        } else {
+            HopKind::Transfer
+        };
+
+        Self {
+            source,
+            destination,
+            rate,
+            fee,
+            kind,
+            timestamp,
+        }
+    }
+
+    /// Gets the vertex the hop starts from.
+    pub fn source(&self) -> &ExchangeVertex {
+        &self.source
+    }
+
+    /// Gets the vertex the hop ends at.
+    pub fn destination(&self) -> &ExchangeVertex {
+        &self.destination
+    }
+
+    /// Gets the rate applied while moving across the hop.
+    pub fn rate(&self) -> Decimal {
+        self.rate
+    }
+
+    /// Gets the fixed fee deducted before moving across the hop, in units of
+    /// the currency of its source vertex.
+    pub fn fee(&self) -> Decimal {
+        self.fee
+    }
+
+    /// Gets the kind of the hop.
+    pub fn kind(&self) -> HopKind {
+        self.kind
+    }
+
+    /// Gets the timestamp of the price update the rate of a trade comes from.
+    /// Transfers don't come from a price update, so they have none.
+    pub fn timestamp(&self) -> Option<&DateTime<Utc>> {
+        self.timestamp.as_ref()
+    }
+}
+
+impl BestRate {
+    /// Create a new `BestRate` for a request, from the hops of its path.
+    /// The rate is the product of the rates of every hop.
+    pub fn new(request: ExchangeRateRequest, hops: Vec<Hop>) -> Self {
+        let rate = hops.iter().fold(1.into(), |rate, hop| rate * hop.rate);
+        Self {
+            request,
+            hops,
+            rate,
+        }
+    }
+
+    /// Gets the exchange rate request answered by the best rate.
+    pub fn request(&self) -> &ExchangeRateRequest {
+        &self.request
+    }
+
+    /// Gets the hops of the path, in order.
+    pub fn hops(&self) -> &[Hop] {
+        &self.hops
+    }
+
+    /// Gets the cumulative rate of moving along the whole path.
+    pub fn rate(&self) -> Decimal {
+        self.rate
+    }
+
+    /// Gets the amount received at the destination for a given amount of the
+    /// source currency, after deducting the fixed fee of every hop.
+    ///
+    /// Returns zero if the fees consume the whole amount.
+    pub fn net_amount(&self, amount: Decimal) -> Decimal {
+        self.hops.iter().fold(amount, |amount, hop| {
+            ((amount - hop.fee) * hop.rate).max(Decimal::from(0))
+        })
+    }
+
+    /// Gets every vertex on the path, in order, from the source to the destination.
+    pub fn vertices(&self) -> Vec<ExchangeVertex> {
+        let (source, _) = ExchangeVertexPair::from(&self.request);
+        let destinations = self.hops.iter().map(|hop| hop.destination);
+
+        Some(source).into_iter().chain(destinations).collect()
+    }
+
+    /// Gets the timestamps of all price updates which contributed to the rate.
+    pub fn timestamps(&self) -> Vec<&DateTime<Utc>> {
+        self.hops.iter().filter_map(Hop::timestamp).collect()
+    }
+}
+
+impl Display for BestRate {
+    /// Format the best rate as a `BEST_RATES_BEGIN ... BEST_RATES_END` block.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (src, dst) = ExchangeVertexPair::from(&self.request);
+
+        writeln!(
+            f,
+            "BEST_RATES_BEGIN {} {} {} {} {}",
+            src.exchange(),
+            src.currency(),
+            dst.exchange(),
+            dst.currency(),
+            self.rate,
+        )?;
+
+        for node in self.vertices() {
+            writeln!(f, "<{}, {}>", node.exchange(), node.currency())?;
+        }
+
+        write!(f, "BEST_RATES_END")
+    }
+}
@@ -0,0 +1,222 @@
+use crate::exchange::{Currency, ExchangeType, ExchangeVertex};
+use petgraph::graphmap::DiGraphMap;
+use rust_decimal::Decimal;
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet, VecDeque},
+};
+
+/// Restrictions on the routes considered for an exchange rate request.
+///
+/// A vertex is only allowed if both its exchange and currency are allowed:
+/// not denied, and part of the allow-list when one is set.
+#[derive(Debug, Clone, Default)]
+pub struct RouteConstraints {
+    max_hops: Option<usize>,
+    max_transfers: Option<usize>,
+    allowed_exchanges: HashSet<ExchangeType>,
+    denied_exchanges: HashSet<ExchangeType>,
+    allowed_currencies: HashSet<Currency>,
+    denied_currencies: HashSet<Currency>,
+}
+
+/// A state of the constrained search: a vertex, along with the number of
+/// transfers made to reach it.
+type State = (ExchangeVertex, usize);
+
+impl RouteConstraints {
+    /// Set the maximum number of trades and transfers of a route.
+    pub fn with_max_hops(mut self, max_hops: usize) -> Self {
+        self.max_hops = Some(max_hops);
+        self
+    }
+
+    /// Set the maximum number of transfers between exchanges of a route.
+    pub fn with_max_transfers(mut self, max_transfers: usize) -> Self {
+        self.max_transfers = Some(max_transfers);
+        self
+    }
+
+    /// Only allow routes going through the allowed exchanges. Can be called
+    /// several times to allow more exchanges.
+    pub fn allow_exchange(mut self, exchange: ExchangeType) -> Self {
+        self.allowed_exchanges.insert(exchange);
+        self
+    }
+
+    /// Never allow routes going through an exchange.
+    pub fn deny_exchange(mut self, exchange: ExchangeType) -> Self {
+        self.denied_exchanges.insert(exchange);
+        self
+    }
+
+    /// Only allow routes going through the allowed currencies. Can be called
+    /// several times to allow more currencies.
+    pub fn allow_currency(mut self, currency: Currency) -> Self {
+        self.allowed_currencies.insert(currency);
+        self
+    }
+
+    /// Never allow routes going through a currency.
+    pub fn deny_currency(mut self, currency: Currency) -> Self {
+        self.denied_currencies.insert(currency);
+        self
+    }
+
+    /// Gets the maximum number of hops of a route, if any.
+    pub fn max_hops(&self) -> Option<usize> {
+        self.max_hops
+    }
+
+    /// Gets the maximum number of transfers of a route, if any.
+    pub fn max_transfers(&self) -> Option<usize> {
+        self.max_transfers
+    }
+
+    /// Checks if a route may go through a vertex.
+    pub fn allows(&self, vertex: &ExchangeVertex) -> bool {
+        let (exchange, currency) = (vertex.exchange(), vertex.currency());
+
+        !self.denied_exchanges.contains(exchange)
+            && !self.denied_currencies.contains(currency)
+            && (self.allowed_exchanges.is_empty() || self.allowed_exchanges.contains(exchange))
+            && (self.allowed_currencies.is_empty() || self.allowed_currencies.contains(currency))
+    }
+
+    /// Gets the fewest transfers needed to reach every vertex from `u`, only
+    /// going through the vertices the constraints allow. Vertices which can't
+    /// be reached within the maximum number of transfers are left out.
+    pub(crate) fn transfers_from(
+        &self,
+        graph: &DiGraphMap<ExchangeVertex, Decimal>,
+        u: ExchangeVertex,
+    ) -> HashMap<ExchangeVertex, usize> {
+        let mut transfers = HashMap::new();
+        if !graph.contains_node(u) || !self.allows(&u) {
+            return transfers;
+        }
+
+        // Trades are free and transfers cost one, so trades are explored first.
+        let mut queue = VecDeque::from(vec![(u, 0)]);
+        while let Some((x, count)) = queue.pop_front() {
+            if transfers.get(&x).is_some_and(|&known| known <= count) {
+                continue;
+            }
+            transfers.insert(x, count);
+
+            for (_, y, w) in graph.edges(x) {
+                if x == y || *w <= Decimal::from(0) || !self.allows(&y) {
+                    continue;
+                }
+
+                if x.exchange() == y.exchange() {
+                    queue.push_front((y, count));
+                } else if self.max_transfers.is_none_or(|max| count < max) {
+                    queue.push_back((y, count + 1));
+                }
+            }
+        }
+
+        transfers
+    }
+
+    /// Find the route with the best rate between two vertices honoring the
+    /// constraints, using the Bellman Ford's algorithm one hop at a time.
+    ///
+    /// Without profitable cycles, removing a cycle from a route never lowers
+    /// its rate, so the route found is loop-free.
+    pub(crate) fn best_route(
+        &self,
+        graph: &DiGraphMap<ExchangeVertex, Decimal>,
+        u: ExchangeVertex,
+        v: ExchangeVertex,
+    ) -> Option<Vec<ExchangeVertex>> {
+        if !graph.contains_node(u) || !graph.contains_node(v) {
+            return None;
+        }
+
+        if !self.allows(&u) || !self.allows(&v) {
+            return None;
+        }
+
+        if u == v {
+            return Some(vec![u]);
+        }
+
+        let max_hops = self
+            .max_hops
+            .unwrap_or_else(|| graph.node_count().saturating_sub(1));
+
+        // The best rate of every state reached at each hop, with its predecessor.
+        let mut layers: Vec<HashMap<State, (Decimal, State)>> = vec![];
+        let mut frontier: HashMap<State, Decimal> = HashMap::new();
+        frontier.insert((u, 0), 1.into());
+
+        let mut best: Option<(Decimal, usize, State)> = None;
+
+        for hop in 0..max_hops {
+            let mut layer: HashMap<State, (Decimal, State)> = HashMap::new();
+
+            for (&(x, transfers), &rate) in frontier.iter() {
+                for (_, y, w) in graph.edges(x) {
+                    if x == y || *w <= Decimal::from(0) || !self.allows(&y) {
+                        continue;
+                    }
+
+                    let next = if x.exchange() == y.exchange() {
+                        (y, transfers)
+                    } else {
+                        (y, transfers + 1)
+                    };
+
+                    if self.max_transfers.is_some_and(|max| next.1 > max) {
+                        continue;
+                    }
+
+                    // Ties are broken by the smallest predecessor, so the route
+                    // found doesn't depend on the iteration order.
+                    let total = rate * *w;
+                    let previous = (x, transfers);
+                    if layer.get(&next).is_none_or(|&(other, other_previous)| {
+                        (other, Reverse(other_previous)) < (total, Reverse(previous))
+                    }) {
+                        layer.insert(next, (total, previous));
+                    }
+                }
+            }
+
+            // Routes with fewer hops, then fewer transfers, are preferred on ties.
+            for (&(y, transfers), &(rate, _)) in layer.iter() {
+                if y == v
+                    && best.is_none_or(|(other, other_hop, (_, other_transfers))| {
+                        (other, Reverse(other_hop), Reverse(other_transfers))
+                            < (rate, Reverse(hop), Reverse(transfers))
+                    })
+                {
+                    best = Some((rate, hop, (y, transfers)));
+                }
+            }
+
+            if layer.is_empty() {
+                break;
+            }
+
+            frontier = layer
+                .iter()
+                .map(|(state, (rate, _))| (*state, *rate))
+                .collect();
+            layers.push(layer);
+        }
+
+        let (_, hop, mut state) = best?;
+        let mut route = vec![state.0];
+
+        for layer in layers[..=hop].iter().rev() {
+            state = layer[&state].1;
+            route.push(state.0);
+        }
+
+        route.reverse();
+        Some(route)
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{
+    exchange::{BestRate, ExchangeType, ExchangeVertex, ExchangeVertexPair, RateGraphError},
+    input::ExchangeRateRequest,
+};
+use chrono::{DateTime, Duration, Utc};
+use itertools::Itertools;
+use petgraph::graphmap::DiGraphMap;
+use rust_decimal::Decimal;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+/// Color of the vertices and edges of the highlighted route.
+const ROUTE_COLOR: &str = "red";
+
+/// Options of the DOT export of a rate graph, rendered with Graphviz.
+#[derive(Debug, Clone, Default)]
+pub struct DotOptions {
+    route: Option<ExchangeRateRequest>,
+    age_reference: Option<DateTime<Utc>>,
+}
+
+impl DotOptions {
+    /// Highlight the route returned by `RateGraph::full_path` for a request.
+    /// If the request has no route, the error is shown as the label of the graph.
+    pub fn with_route(mut self, request: ExchangeRateRequest) -> Self {
+        self.route = Some(request);
+        self
+    }
+
+    /// Measure the age of markets from `time`, rather than from the most
+    /// recent price update of the graph.
+    pub fn with_age_reference(mut self, time: DateTime<Utc>) -> Self {
+        self.age_reference = Some(time);
+        self
+    }
+
+    /// Gets the request whose route is highlighted, if any.
+    pub fn route(&self) -> Option<&ExchangeRateRequest> {
+        self.route.as_ref()
+    }
+}
+
+/// The parts of a rate graph drawn by its DOT export.
+pub(crate) struct Drawing<'a> {
+    pub graph: &'a DiGraphMap<ExchangeVertex, Decimal>,
+    pub markets: &'a HashMap<ExchangeVertexPair, DateTime<Utc>>,
+    pub suspended: &'a HashSet<ExchangeType>,
+    pub latest: Option<DateTime<Utc>>,
+}
+
+impl<'a> Drawing<'a> {
+    /// Render the graph in the DOT language, with a cluster per exchange.
+    ///
+    /// Trades are labeled with their rate and the age of their market, while
+    /// transfers are dashed and only labeled with their rate. The loops of
+    /// every vertex onto itself aren't drawn.
+    pub fn render(
+        &self,
+        options: &DotOptions,
+        route: Option<Result<BestRate, RateGraphError>>,
+    ) -> String {
+        let reference = options.age_reference.or(self.latest);
+        let (label, route) = match &route {
+            Some(Ok(best_rate)) => {
+                let (u, v) = ExchangeVertexPair::from(best_rate.request());
+                let label = format!(
+                    "{} to {}: {}",
+                    vertex_label(u),
+                    vertex_label(v),
+                    best_rate.rate()
+                );
+                let hops = best_rate
+                    .hops()
+                    .iter()
+                    .map(|hop| (*hop.source(), *hop.destination()))
+                    .collect();
+                (Some(label), hops)
+            }
+            Some(Err(error)) => (Some(error.to_string()), HashSet::new()),
+            None => (None, HashSet::new()),
+        };
+        let on_route = |vertex: ExchangeVertex| {
+            route
+                .iter()
+                .any(|(src, dst): &ExchangeVertexPair| *src == vertex || *dst == vertex)
+        };
+
+        let mut dot = String::from("digraph {\n");
+        if let Some(label) = label {
+            writeln!(dot, "    label={};", quote(&label)).unwrap();
+            dot += "    labelloc=t;\n";
+        }
+
+        let vertices = self
+            .graph
+            .nodes()
+            .sorted()
+            .group_by(|vertex| *vertex.exchange());
+        for (exchange, vertices) in &vertices {
+            let mut cluster = exchange.to_string();
+            if self.suspended.contains(&exchange) {
+                cluster += " (suspended)";
+            }
+
+            writeln!(
+                dot,
+                "    subgraph {} {{",
+                quote(&format!("cluster_{}", exchange))
+            )
+            .unwrap();
+            writeln!(dot, "        label={};", quote(&cluster)).unwrap();
+            if self.suspended.contains(&exchange) {
+                dot += "        style=dashed;\n";
+            }
+
+            for vertex in vertices {
+                write!(
+                    dot,
+                    "        {} [label={}",
+                    vertex_id(vertex),
+                    quote(&vertex.currency().to_string())
+                )
+                .unwrap();
+                if on_route(vertex) {
+                    write!(dot, ", color={}, penwidth=2", ROUTE_COLOR).unwrap();
+                }
+                dot += "];\n";
+            }
+            dot += "    }\n";
+        }
+
+        let edges = self
+            .graph
+            .all_edges()
+            .filter(|(src, dst, _)| src != dst)
+            .sorted_by_key(|(src, dst, _)| (*src, *dst));
+        for (src, dst, rate) in edges {
+            let mut attributes = vec![];
+            match self.markets.get(&(src, dst)) {
+                Some(timestamp) if src.exchange() == dst.exchange() => {
+                    let label = match reference {
+                        Some(reference) => {
+                            format!("{}\n{}", rate, format_age(reference - *timestamp))
+                        }
+                        None => rate.to_string(),
+                    };
+                    attributes.push(format!("label={}", quote(&label)));
+                }
+                _ => {
+                    attributes.push(format!("label={}", quote(&rate.to_string())));
+                    attributes.push("style=dashed".to_string());
+                }
+            }
+            if route.contains(&(src, dst)) {
+                attributes.push(format!("color={}", ROUTE_COLOR));
+                attributes.push(format!("fontcolor={}", ROUTE_COLOR));
+                attributes.push("penwidth=2".to_string());
+            }
+
+            writeln!(
+                dot,
+                "    {} -> {} [{}];",
+                vertex_id(src),
+                vertex_id(dst),
+                attributes.join(", ")
+            )
+            .unwrap();
+        }
+
+        dot += "}\n";
+        dot
+    }
+}
+
+/// Gets the identifier of a vertex, as `"<exchange>:<currency>"`.
+fn vertex_id(vertex: ExchangeVertex) -> String {
+    quote(&vertex_label(vertex))
+}
+
+/// Gets the label of a vertex, as `<exchange>:<currency>`.
+fn vertex_label(vertex: ExchangeVertex) -> String {
+    format!("{}:{}", vertex.exchange(), vertex.currency())
+}
+
+/// Quote a string as a DOT identifier, escaping its quotes and line breaks.
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\\\"").replace('\n', "\\n"))
+}
+
+/// Format the age of a market with its two most significant units, e.g.
+/// `2h 5m` or `42s`. Markets updated after the reference are zero seconds old.
+fn format_age(age: Duration) -> String {
+    let seconds = age.num_seconds().max(0);
+    let (days, hours, minutes) = (seconds / 86_400, seconds / 3_600 % 24, seconds / 60 % 60);
+
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds % 60)
+    } else {
+        format!("{}s", seconds)
+    }
+}
@@ -1,7 +1,9 @@
 use crate::exchange::resources::{Currency, ExchangeType};
+use std::fmt::{self, Display};
 
 /// A exchange vertex representation of a given price update.
 #[derive(Debug, Eq, PartialEq, Hash, PartialOrd, Clone, Copy, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExchangeVertex(ExchangeType, Currency);
 
 /// ExchangeVertexPair comprising of a both (Source, Destination).
@@ -23,3 +25,10 @@ impl ExchangeVertex {
         &self.0
     }
 }
+
+impl Display for ExchangeVertex {
+    /// Format the vertex as `<exchange, currency>`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<{}, {}>", self.0, self.1)
+    }
+}
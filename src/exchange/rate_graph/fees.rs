@@ -0,0 +1,81 @@
+use crate::exchange::{Currency, ExchangeType, ExchangeVertex};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// The fees and costs charged while trading on, or transferring across exchanges.
+///
+/// Trading fees and transfer factors are applied to the rates of the graph,
+/// while withdrawal fees are fixed amounts, only applied once an amount is
+/// converted along a path.
+#[derive(Debug, Clone, Default)]
+pub struct FeeModel {
+    pub(crate) trading_fees: HashMap<ExchangeType, Decimal>,
+    pub(crate) withdrawal_fees: HashMap<Currency, Decimal>,
+    pub(crate) transfer_factors: HashMap<(ExchangeType, ExchangeType), Decimal>,
+}
+
+impl FeeModel {
+    /// Set the fee charged by an exchange on every trade, as a fraction of the
+    /// traded amount (e.g. `0.0025` for a 0.25% taker fee).
+    pub fn with_trading_fee(mut self, exchange: ExchangeType, fee: Decimal) -> Self {
+        self.trading_fees.insert(exchange, fee);
+        self
+    }
+
+    /// Set the fixed fee charged when withdrawing a currency to another exchange,
+    /// in units of that currency.
+    pub fn with_withdrawal_fee(mut self, currency: Currency, fee: Decimal) -> Self {
+        self.withdrawal_fees.insert(currency, fee);
+        self
+    }
+
+    /// Set the factor applied to any amount transferred from one exchange to
+    /// another (e.g. `0.999` if 0.1% is lost on the way).
+    pub fn with_transfer_factor(
+        mut self,
+        source: ExchangeType,
+        destination: ExchangeType,
+        factor: Decimal,
+    ) -> Self {
+        self.transfer_factors.insert((source, destination), factor);
+        self
+    }
+
+    /// Gets the trading fee of an exchange. Defaults to zero.
+    pub fn trading_fee(&self, exchange: ExchangeType) -> Decimal {
+        self.trading_fees
+            .get(&exchange)
+            .cloned()
+            .unwrap_or_else(|| 0.into())
+    }
+
+    /// Gets the withdrawal fee of a currency. Defaults to zero.
+    pub fn withdrawal_fee(&self, currency: Currency) -> Decimal {
+        self.withdrawal_fees
+            .get(&currency)
+            .cloned()
+            .unwrap_or_else(|| 0.into())
+    }
+
+    /// Gets the factor of a transfer between two exchanges. Defaults to one.
+    pub fn transfer_factor(&self, source: ExchangeType, destination: ExchangeType) -> Decimal {
+        self.transfer_factors
+            .get(&(source, destination))
+            .cloned()
+            .unwrap_or_else(|| 1.into())
+    }
+
+    /// Gets the net rate of a trade on an exchange, after its trading fee.
+    pub(crate) fn trade_rate(&self, exchange: ExchangeType, rate: Decimal) -> Decimal {
+        rate * (Decimal::from(1) - self.trading_fee(exchange))
+    }
+
+    /// Gets the net rate of transferring a currency between two vertices.
+    pub(crate) fn transfer_rate(
+        &self,
+        source: ExchangeVertex,
+        destination: ExchangeVertex,
+    ) -> Decimal {
+        self.transfer_factor(*source.exchange(), *destination.exchange())
+    }
+}
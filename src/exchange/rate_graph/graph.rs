@@ -1,13 +1,36 @@
 use crate::{
-    exchange::{Currency, ExchangeVertex, ExchangeVertexPair, FullPath, Path, PathCost},
-    input::{ExchangeRateRequest, PriceUpdate},
+    exchange::{
+        rate_graph::{
+            arbitrage::arbitrage_cycles,
+            dot::Drawing,
+            history::PriceHistory,
+            price_log::{read_log, LogError},
+            quote::Market,
+            report::validate,
+            snapshot::{Snapshot, SnapshotError},
+            view::Router,
+        },
+        ArbitrageCycle, BestRate, Currency, DotOptions, ExchangeType, ExchangeVertex,
+        ExchangeVertexPair, FeeModel, InsertReport, LogRecord, Path, Quote, RateView,
+        RouteConstraints,
+    },
+    input::{
+        CsvError, CsvMapping, CsvReader, CsvReport, ExchangeRateRequest, OrderBook, PriceUpdate,
+    },
     utils::map_utils::update_with_recent,
 };
-use chrono::NaiveDateTime;
+use chrono::{DateTime, Duration, Utc};
 use itertools::Itertools;
 use petgraph::graphmap::DiGraphMap;
 use rust_decimal::Decimal;
-use std::{collections::HashMap, iter::FromIterator};
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt::{self, Display},
+    io::{self, BufRead, Write},
+    iter::FromIterator,
+    mem,
+};
 
 /// Errors that occur while utilizing a rate graph.
 #[derive(Debug)]
@@ -17,34 +40,164 @@ pub enum RateGraphError {
 
     /// There exists no connection between vertex pairs in thr graphs.
     NoEdgesBetweenNodes(ExchangeVertex, ExchangeVertex),
+
+    /// The route between the vertex pairs can go through a profitable cycle,
+    /// so there exists no best rate between them.
+    UnboundedRate(ExchangeVertex, ExchangeVertex, ArbitrageCycle),
+
+    /// The order books between the vertex pairs are not deep enough to
+    /// convert the whole amount.
+    InsufficientLiquidity(ExchangeVertex, ExchangeVertex),
+
+    /// There exists no connection between vertex pairs anymore, since the
+    /// markets connecting them expired.
+    StaleRate(ExchangeVertex, ExchangeVertex),
+
+    /// There exists a connection between vertex pairs, but none of them
+    /// honors the route constraints.
+    UnsatisfiedConstraints(ExchangeVertex, ExchangeVertex),
+
+    /// The amount to quote is zero or negative.
+    NonPositiveAmount(Decimal),
+}
+
+impl Display for RateGraphError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use RateGraphError::*;
+
+        match self {
+            UnknownExchange(vertex) => write!(f, "unknown exchange vertex {}", vertex),
+            NoEdgesBetweenNodes(u, v) => write!(f, "no route from {} to {}", u, v),
+            UnboundedRate(u, v, cycle) => {
+                let vertices: Vec<String> =
+                    cycle.vertices().iter().map(|v| v.to_string()).collect();
+                write!(
+                    f,
+                    "unbounded rate from {} to {} through arbitrage cycle {}",
+                    u,
+                    v,
+                    vertices.join(" ")
+                )
+            }
+            InsufficientLiquidity(u, v) => {
+                write!(f, "insufficient liquidity from {} to {}", u, v)
+            }
+            StaleRate(u, v) => write!(f, "stale rates from {} to {}", u, v),
+            UnsatisfiedConstraints(u, v) => {
+                write!(f, "no route from {} to {} honors the constraints", u, v)
+            }
+            NonPositiveAmount(amount) => write!(f, "amount {} isn't positive", amount),
+        }
+    }
 }
 
+impl Error for RateGraphError {}
+
+/// Number of rows of a CSV export inserted at once.
+const CSV_BATCH_SIZE: usize = 1024;
+
 /// An time ordered exchange graph that pairs exchange vertex to the time they
 /// are created (i.e. their timestamp).
 ///
 /// Upon the insert of a new exchange vertex, if the new time value
 /// is more recent than the previous, update the time for the given vertex,
 /// or else create the vertex for the first time.
-type RecentExchangeMap = HashMap<ExchangeVertex, NaiveDateTime>;
+type RecentExchangeMap = HashMap<ExchangeVertex, DateTime<Utc>>;
 
 /// A graph reresentation of all exchanges connected together by a
 /// specified weight as the rate if transactions.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RateGraph {
     exchange_prices: HashMap<Currency, RecentExchangeMap>,
+    markets: HashMap<ExchangeVertexPair, DateTime<Utc>>,
+    books: HashMap<ExchangeVertexPair, OrderBook>,
+    prices: HashMap<ExchangeVertexPair, PriceUpdate>,
+    history: PriceHistory,
+    expired: HashMap<ExchangeVertexPair, DateTime<Utc>>,
+    suspended: HashSet<ExchangeType>,
+    suspended_edges: HashMap<ExchangeVertexPair, Decimal>,
+    max_age: Option<Duration>,
+    latest: Option<DateTime<Utc>>,
     graph: DiGraphMap<ExchangeVertex, Decimal>,
     path: Path<ExchangeVertex, Decimal>,
+    cycles: Vec<ArbitrageCycle>,
+    raised: Vec<ExchangeVertexPair>,
+    fees: FeeModel,
 }
 
 impl RateGraph {
+    /// Create an empty rate graph, which applies the specified fees to the
+    /// rates of every trade and transfer.
+    pub fn with_fees(fees: FeeModel) -> Self {
+        Self {
+            fees,
+            ..Self::default()
+        }
+    }
+
+    /// Get the fees applied to the rates of the graph.
+    pub fn fees(&self) -> &FeeModel {
+        &self.fees
+    }
+
+    /// Set the fees applied to the rates of the graph, recalculating the rates
+    /// of every market from its latest price update.
+    pub fn set_fees(&mut self, fees: FeeModel) {
+        let mut markets: Vec<_> = self.prices.iter().collect();
+        markets.sort_by_key(|(market, _)| **market);
+        let prices = markets
+            .into_iter()
+            .map(|(_, price)| price.clone())
+            .collect();
+
+        let mut graph = Self::with_fees(fees);
+        graph.suspended = mem::take(&mut self.suspended);
+        graph.expired = mem::take(&mut self.expired);
+        graph.max_age = self.max_age;
+        graph.latest = self.latest;
+
+        let history = mem::take(&mut self.history);
+        *self = graph.rebuild(prices);
+        self.history = history;
+    }
+
+    /// Set the maximum age of the markets in the graph. On every insert, markets
+    /// whose last price update is older than the most recent update by more than
+    /// `max_age` are expired.
+    pub fn set_max_age(&mut self, max_age: Option<Duration>) {
+        self.max_age = max_age;
+    }
+
+    /// Set the number of price updates kept per market for point-in-time
+    /// queries, `DEFAULT_HISTORY_DEPTH` by default.
+    pub fn set_history_depth(&mut self, depth: usize) {
+        self.history.set_depth(depth);
+    }
+
+    /// Get the markets which expired, with the timestamp of their last price update.
+    pub fn expired_markets(&self) -> &HashMap<ExchangeVertexPair, DateTime<Utc>> {
+        &self.expired
+    }
+
     /// Get the inner graph of a rate graph.
     pub fn graph(&self) -> &DiGraphMap<ExchangeVertex, Decimal> {
         &self.graph
     }
 
+    /// Get the best paths computed between every pair of nodes in the graph.
+    pub fn path(&self) -> &Path<ExchangeVertex, Decimal> {
+        &self.path
+    }
+
+    /// Get the profitable cycles found in the graph during the last insert.
+    pub fn arbitrage_cycles(&self) -> &[ArbitrageCycle] {
+        &self.cycles
+    }
+
     /// Calculate the best possible echange rates using the Floyd Warshall's
     /// algorithm.
     fn calculate_best_rates(&mut self) {
+        self.path = Path::default();
         self.path.floyd_warshall(&self.graph, 0);
     }
 
@@ -73,46 +226,609 @@ impl RateGraph {
         dst: ExchangeVertex,
         forward_factor: Decimal,
         backward_factor: Decimal,
+        repair: bool,
     ) {
-        self.graph.add_edge(src, dst, forward_factor);
-        self.graph.add_edge(dst, src, backward_factor);
-        self.graph.add_edge(src, src, 0.into());
-        self.graph.add_edge(dst, dst, 0.into());
+        self.set_edge(src, dst, forward_factor, repair);
+        self.set_edge(dst, src, backward_factor, repair);
+        self.set_edge(src, src, 0.into(), repair);
+        self.set_edge(dst, dst, 0.into(), repair);
+    }
+
+    /// Set the weight of a single directed edge. If `repair` is set, only the
+    /// best rates affected by the change are immediately recalculated.
+    fn set_edge(
+        &mut self,
+        src: ExchangeVertex,
+        dst: ExchangeVertex,
+        weight: Decimal,
+        repair: bool,
+    ) {
+        for node in &[src, dst] {
+            if !self.graph.contains_node(*node) {
+                self.graph.add_node(*node);
+
+                if repair {
+                    self.path.insert_node(*node, 0);
+                }
+            }
+        }
+
+        // The edges of suspended exchanges are kept aside until they resume.
+        if self.is_suspended(src) || self.is_suspended(dst) {
+            self.suspended_edges.insert((src, dst), weight);
+            return;
+        }
+
+        let old = self.graph.add_edge(src, dst, weight);
+        if old.is_none_or(|old| old < weight) {
+            self.raised.push((src, dst));
+        }
+        if repair && old != Some(weight) {
+            self.path.update_edge(&self.graph, src, dst, old, 0);
+        }
+    }
+
+    /// Remove a single directed edge, and repair the best rates that went through it.
+    fn remove_edge(&mut self, src: ExchangeVertex, dst: ExchangeVertex) {
+        self.suspended_edges.remove(&(src, dst));
+
+        let old = self.graph.remove_edge(src, dst);
+        if old.is_some() {
+            self.path.update_edge(&self.graph, src, dst, old, 0);
+        }
+    }
+
+    /// Checks if a vertex belongs to a suspended exchange.
+    fn is_suspended(&self, vertex: ExchangeVertex) -> bool {
+        self.suspended.contains(vertex.exchange())
+    }
+
+    /// Remove a market of an exchange from the graph, along with its order book,
+    /// and recalculate the best rates that went through it. Its history is kept,
+    /// so that point-in-time queries before the most recent update of the graph
+    /// still see the market.
+    ///
+    /// Vertices left without any market are removed as well, together with their
+    /// transfers to other exchanges. Returns `false` if the market didn't exist.
+    pub fn remove_market(
+        &mut self,
+        exchange: ExchangeType,
+        base: Currency,
+        quote: Currency,
+    ) -> bool {
+        let ex1 = ExchangeVertex::new(exchange, base);
+        let ex2 = ExchangeVertex::new(exchange, quote);
+
+        let known = [(ex1, ex2), (ex2, ex1)]
+            .iter()
+            .any(|market| self.markets.contains_key(market) || self.expired.contains_key(market));
+        if !known {
+            return false;
+        }
+
+        for &(src, dst) in &[(ex1, ex2), (ex2, ex1)] {
+            self.markets.remove(&(src, dst));
+            self.expired.remove(&(src, dst));
+            self.books.remove(&(src, dst));
+            self.prices.remove(&(src, dst));
+            self.remove_edge(src, dst);
+        }
+        if let Some(latest) = self.latest {
+            self.history.remove(ex1, ex2, latest);
+        }
+
+        for &vertex in &[ex1, ex2] {
+            let traded = self
+                .markets
+                .keys()
+                .chain(self.expired.keys())
+                .any(|(src, _)| *src == vertex);
+
+            if !traded {
+                self.remove_vertex(vertex);
+            }
+        }
+
+        self.update_cycles();
+        true
+    }
+
+    /// Remove a vertex along with all of its edges from the graph.
+    fn remove_vertex(&mut self, vertex: ExchangeVertex) {
+        if let Some(exchanges) = self.exchange_prices.get_mut(vertex.currency()) {
+            exchanges.remove(&vertex);
+
+            if exchanges.is_empty() {
+                self.exchange_prices.remove(vertex.currency());
+            }
+        }
+
+        let edges: Vec<ExchangeVertexPair> = self
+            .graph
+            .all_edges()
+            .map(|(src, dst, _)| (src, dst))
+            .chain(self.suspended_edges.keys().cloned())
+            .filter(|(src, dst)| *src == vertex || *dst == vertex)
+            .collect();
+
+        for (src, dst) in edges {
+            self.remove_edge(src, dst);
+        }
+
+        self.graph.remove_node(vertex);
+        self.path.remove_node(vertex);
+    }
+
+    /// Suspend every market and transfer of an exchange, for example during an
+    /// outage, and recalculate the best rates that went through them.
+    ///
+    /// Price updates of a suspended exchange are still recorded, and applied once
+    /// it resumes.
+    pub fn suspend_exchange(&mut self, exchange: ExchangeType) {
+        if !self.suspended.insert(exchange) {
+            return;
+        }
+
+        let edges: Vec<(ExchangeVertex, ExchangeVertex, Decimal)> = self
+            .graph
+            .all_edges()
+            .filter(|(src, dst, _)| self.is_suspended(*src) || self.is_suspended(*dst))
+            .map(|(src, dst, weight)| (src, dst, *weight))
+            .collect();
+
+        for (src, dst, weight) in edges {
+            self.remove_edge(src, dst);
+            self.suspended_edges.insert((src, dst), weight);
+        }
+
+        self.update_cycles();
+    }
+
+    /// Resume a suspended exchange, restoring its markets and transfers with
+    /// their most recent rates.
+    pub fn resume_exchange(&mut self, exchange: ExchangeType) {
+        if !self.suspended.remove(&exchange) {
+            return;
+        }
+
+        let edges: Vec<(ExchangeVertexPair, Decimal)> = self
+            .suspended_edges
+            .iter()
+            .filter(|((src, dst), _)| !self.is_suspended(*src) && !self.is_suspended(*dst))
+            .map(|(edge, weight)| (*edge, *weight))
+            .collect();
+
+        for ((src, dst), weight) in edges {
+            self.suspended_edges.remove(&(src, dst));
+            self.set_edge(src, dst, weight, true);
+        }
+
+        self.update_cycles();
+    }
+
+    /// Gets the exchanges currently suspended.
+    pub fn suspended_exchanges(&self) -> &HashSet<ExchangeType> {
+        &self.suspended
+    }
+
+    /// Remove the trade edges of every market whose last price update happened
+    /// before `cutoff`, and recalculate the best rates that went through them.
+    ///
+    /// Returns the expired markets, in both directions. Requests that can only
+    /// be routed through them are reported as `RateGraphError::StaleRate`.
+    pub fn expire_before(&mut self, cutoff: DateTime<Utc>) -> Vec<ExchangeVertexPair> {
+        let expired = self.expire(cutoff);
+        self.update_cycles();
+        expired
+    }
+
+    /// Remove the trade edges of every market whose last price update happened
+    /// before `cutoff`, without searching the graph for profitable cycles.
+    fn expire(&mut self, cutoff: DateTime<Utc>) -> Vec<ExchangeVertexPair> {
+        let stale: Vec<(ExchangeVertexPair, DateTime<Utc>)> = self
+            .markets
+            .iter()
+            .filter(|(_, timestamp)| **timestamp < cutoff)
+            .map(|(market, timestamp)| (*market, *timestamp))
+            .collect();
+
+        for &((src, dst), timestamp) in stale.iter() {
+            self.markets.remove(&(src, dst));
+            self.books.remove(&(src, dst));
+            self.prices.remove(&(src, dst));
+            self.expired.insert((src, dst), timestamp);
+            self.remove_edge(src, dst);
+        }
+
+        stale.into_iter().map(|(market, _)| market).collect()
     }
 
     /// Returns the most optimal sequence of trades and transfers across exchanges
     /// for the specified exchange rate request.
-    pub fn full_path(
+    pub fn full_path(&self, request: &ExchangeRateRequest) -> Result<BestRate, RateGraphError> {
+        self.router().full_path(request)
+    }
+
+    /// Returns the most optimal sequence of trades and transfers across exchanges
+    /// for the specified exchange rate request, which honors the given constraints.
+    pub fn full_path_with(
+        &self,
+        request: &ExchangeRateRequest,
+        constraints: &RouteConstraints,
+    ) -> Result<BestRate, RateGraphError> {
+        self.router().full_path_with(request, constraints)
+    }
+
+    /// Returns the most optimal sequence of trades and transfers across exchanges
+    /// for the specified exchange rate request, using only the price updates
+    /// whose timestamp isn't after `as_of`.
+    ///
+    /// Only the last few updates of every market are kept (see
+    /// `RateGraph::set_history_depth`), so a market whose history doesn't reach
+    /// back to `as_of` is treated as unknown at that time. Exchange suspensions
+    /// aren't part of the history, and are ignored.
+    ///
+    /// Every call rebuilds the graph as of that time with `RateGraph::as_of`,
+    /// which costs as much as building it from scratch. To answer several
+    /// requests at the same time, build that graph once instead.
+    pub fn full_path_as_of(
+        &self,
+        request: &ExchangeRateRequest,
+        as_of: DateTime<Utc>,
+    ) -> Result<BestRate, RateGraphError> {
+        self.as_of(as_of).full_path(request)
+    }
+
+    /// Build the graph from the price updates of the history whose timestamp
+    /// isn't after `as_of`, with the same fees and maximum age.
+    ///
+    /// The best rates are recalculated from scratch, in cubic time in the
+    /// number of vertices.
+    pub fn as_of(&self, as_of: DateTime<Utc>) -> RateGraph {
+        let mut graph = Self::with_fees(self.fees.clone()).rebuild(self.history.as_of(as_of));
+        graph.max_age = self.max_age;
+
+        if let (Some(max_age), Some(latest)) = (self.max_age, graph.latest) {
+            graph.expire_before(latest - max_age);
+        }
+
+        graph
+    }
+
+    /// Returns up to `k` loop-free sequences of trades and transfers for the
+    /// specified exchange rate request, ranked from the best rate.
+    ///
+    /// The first one is always as good as the one returned by `RateGraph::full_path`,
+    /// and the others can be used as fallbacks.
+    pub fn k_best_paths(
+        &self,
+        request: &ExchangeRateRequest,
+        k: usize,
+    ) -> Result<Vec<BestRate>, RateGraphError> {
+        self.router().k_best_paths(request, k)
+    }
+
+    /// Get a view of the current rates of the graph, which can answer exchange
+    /// rate requests independently from later changes to the graph.
+    pub fn view(&self) -> RateView {
+        RateView {
+            graph: self.graph.clone(),
+            path: self.path.clone(),
+            cycles: self.cycles.clone(),
+            markets: self.markets.clone(),
+            expired: self.expired.clone(),
+            fees: self.fees.clone(),
+        }
+    }
+
+    /// Borrow everything needed to answer exchange rate requests.
+    fn router(&self) -> Router<'_> {
+        Router {
+            graph: &self.graph,
+            path: &self.path,
+            cycles: &self.cycles,
+            markets: &self.markets,
+            expired: &self.expired,
+            fees: &self.fees,
+        }
+    }
+
+    /// Returns a quote for converting a concrete amount of the source currency
+    /// of the specified exchange rate request.
+    ///
+    /// Markets whose price updates carry an order book are walked level by level,
+    /// and the amount may be split across several routes to get the most of the
+    /// destination currency. Other markets are assumed to have infinite liquidity.
+    ///
+    /// The amount must be positive. Only the few routes ranked first by
+    /// `RateGraph::k_best_paths` are considered, however many hops they take.
+    pub fn quote(
         &self,
         request: &ExchangeRateRequest,
-    ) -> Result<(FullPath<ExchangeVertex>, PathCost<Decimal>), RateGraphError> {
-        let (u, v) = ExchangeVertexPair::from(request);
-        self.path
-            .full_path(u, v)
-            .ok_or_else(|| RateGraphError::NoEdgesBetweenNodes(u, v))
+        amount: Decimal,
+    ) -> Result<Quote, RateGraphError> {
+        let market = Market {
+            graph: &self.graph,
+            books: &self.books,
+            fees: &self.fees,
+        };
+
+        market.quote(request, amount)
+    }
+
+    /// Export the graph in the DOT language, to be rendered with Graphviz.
+    ///
+    /// Vertices are clustered by exchange, and every edge is labeled with its
+    /// rate, along with the age of its market for trades. The route of the
+    /// request set in the options, if any, is highlighted.
+    pub fn to_dot(&self, options: &DotOptions) -> String {
+        let drawing = Drawing {
+            graph: &self.graph,
+            markets: &self.markets,
+            suspended: &self.suspended,
+            latest: self.latest,
+        };
+
+        drawing.render(
+            options,
+            options.route().map(|request| self.full_path(request)),
+        )
+    }
+
+    /// Add a given collection of price updates to an empty graph, and calculate
+    /// the best rates once all of them have been added.
+    fn rebuild(mut self, prices: Vec<PriceUpdate>) -> Self {
+        self.insert_edges(prices, false);
+        self.calculate_best_rates();
+        self.cycles = arbitrage_cycles(&self.graph);
+        self.raised.clear();
+        self
+    }
+
+    /// Update the profitable cycles of the graph after some of its edges changed.
+    ///
+    /// Without any profitable cycle, the best rates are exact, and a new cycle
+    /// has to go through an edge whose rate increased: edge (`u`, `v`) closes
+    /// one if its rate times the best rate from `v` back to `u` exceeds one.
+    /// The whole graph is only searched when such an edge exists, or while the
+    /// graph already has profitable cycles. Once those are gone, the best rates
+    /// are recalculated, as they may have been repaired through the cycles.
+    fn update_cycles(&mut self) {
+        let raised = std::mem::take(&mut self.raised);
+
+        if self.cycles.is_empty() {
+            let closes_cycle = raised.iter().any(|&(u, v)| {
+                match (self.graph.edge_weight(u, v), self.path.rate(v, u)) {
+                    (Some(rate), Some(back)) if u != v => *rate * *back > Decimal::from(1),
+                    _ => false,
+                }
+            });
+
+            if closes_cycle {
+                self.cycles = arbitrage_cycles(&self.graph);
+            }
+        } else {
+            self.cycles = arbitrage_cycles(&self.graph);
+
+            if self.cycles.is_empty() {
+                self.calculate_best_rates();
+            }
+        }
+    }
+
+    /// Write a snapshot of the graph: its fees, suspended exchanges, maximum
+    /// market age, the latest price update of every market which didn't expire,
+    /// and the timestamp of the last price update of every market which did.
+    ///
+    /// The best rates aren't part of the snapshot, and are recalculated on load.
+    pub fn save_snapshot(&self, writer: impl Write) -> io::Result<()> {
+        let mut suspended: Vec<ExchangeType> = self.suspended.iter().cloned().collect();
+        suspended.sort();
+
+        let mut expired: Vec<_> = self
+            .expired
+            .iter()
+            .map(|(market, timestamp)| (*market, *timestamp))
+            .collect();
+        expired.sort();
+
+        let mut markets: Vec<_> = self.prices.iter().collect();
+        markets.sort_by_key(|(market, _)| **market);
+
+        let snapshot = Snapshot {
+            fees: self.fees.clone(),
+            suspended,
+            max_age: self.max_age,
+            latest: self.latest,
+            expired,
+            prices: markets
+                .into_iter()
+                .map(|(_, price)| price.clone())
+                .collect(),
+        };
+
+        snapshot.write(writer)
+    }
+
+    /// Restore a graph from a snapshot written by `RateGraph::save_snapshot`.
+    ///
+    /// Snapshots written with another version of the format are rejected.
+    pub fn load_snapshot(reader: impl BufRead) -> Result<Self, SnapshotError> {
+        let snapshot = Snapshot::read(reader)?;
+
+        let mut graph = Self::with_fees(snapshot.fees);
+        graph.suspended = snapshot.suspended.into_iter().collect();
+        graph.max_age = snapshot.max_age;
+        graph.latest = snapshot.latest;
+        graph.expired = snapshot.expired.into_iter().collect();
+        Ok(graph.rebuild(snapshot.prices))
+    }
+
+    /// Replay a log written by a `PriceLog`, applying the changes recorded
+    /// before the first price update whose timestamp is after `as_of`, or all
+    /// of them if unset (see `read_log`).
+    ///
+    /// Replaying the log into the graph restored from a snapshot taken before it
+    /// rebuilds the rates known at any point in time. A truncated final record
+    /// is ignored.
+    pub fn replay(
+        &mut self,
+        reader: impl BufRead,
+        as_of: Option<DateTime<Utc>>,
+    ) -> Result<InsertReport, LogError> {
+        let records = read_log(reader, as_of)?;
+        Ok(self.apply(records))
+    }
+
+    /// Apply a sequence of changes, in order, as recorded by a `PriceLog`.
+    ///
+    /// Consecutive price updates are inserted at once, and only price updates
+    /// are reported.
+    pub fn apply(&mut self, records: impl IntoIterator<Item = LogRecord>) -> InsertReport {
+        let mut report = InsertReport::default();
+        let mut updates = vec![];
+
+        for record in records {
+            match record {
+                LogRecord::Update(update) => {
+                    updates.push(update);
+                    continue;
+                }
+                _ if !updates.is_empty() => report.extend(self.insert(mem::take(&mut updates))),
+                _ => {}
+            }
+
+            match record {
+                LogRecord::Update(_) => {}
+                LogRecord::RemoveMarket(exchange, base, quote) => {
+                    self.remove_market(exchange, base, quote);
+                }
+                LogRecord::Suspend(exchange) => self.suspend_exchange(exchange),
+                LogRecord::Resume(exchange) => self.resume_exchange(exchange),
+                LogRecord::ExpireBefore(cutoff) => {
+                    self.expire_before(cutoff);
+                }
+                LogRecord::MaxAge(max_age) => self.set_max_age(max_age),
+                LogRecord::Fees(fees) => self.set_fees(fees),
+            }
+        }
+
+        if !updates.is_empty() {
+            report.extend(self.insert(updates));
+        }
+
+        report
+    }
+
+    /// Insert the price updates of a CSV export, whose columns are mapped to
+    /// the fields of an update by `mapping`.
+    ///
+    /// Rows are streamed into `RateGraph::insert` in batches. Rows which can't
+    /// be read are skipped, and reported along with their row number, while a
+    /// missing header or column fails the whole export.
+    pub fn insert_csv(
+        &mut self,
+        reader: impl BufRead,
+        mapping: &CsvMapping,
+    ) -> Result<CsvReport, CsvError> {
+        let mut rows = CsvReader::new(reader, mapping)?;
+        let mut report = CsvReport::default();
+
+        let mut done = false;
+        while !done {
+            let mut batch = Vec::with_capacity(CSV_BATCH_SIZE);
+            let mut read = 0;
+
+            for row in rows.by_ref().take(CSV_BATCH_SIZE) {
+                read += 1;
+                match row {
+                    Ok(update) => batch.push(update),
+                    Err(CsvError::Io(error)) => return Err(CsvError::Io(error)),
+                    Err(error) => report.fail(error),
+                }
+            }
+
+            done = read < CSV_BATCH_SIZE;
+            if !batch.is_empty() {
+                report.insert(self.insert(batch));
+            }
+        }
+
+        Ok(report)
     }
 
     /// Insert a given collection of price updates into the a rate map.
-    /// On insert, the best rates are immediately calculates.
-    pub fn insert(&mut self, updates: impl IntoIterator<Item = PriceUpdate>) {
+    /// On insert, the best rates affected by each changed edge are immediately
+    /// recalculated, and the graph is checked once for profitable cycles going
+    /// through the changed edges.
+    ///
+    /// An update older than the last one applied to the same market never
+    /// overwrites its rate. Returns which updates were applied, ignored as
+    /// stale, or rejected as invalid.
+    pub fn insert(&mut self, updates: impl IntoIterator<Item = PriceUpdate>) -> InsertReport {
+        let report = self.insert_edges(updates, true);
+
+        if let (Some(max_age), Some(latest)) = (self.max_age, self.latest) {
+            self.expire(latest - max_age);
+        }
+
+        self.update_cycles();
+        report
+    }
+
+    /// Checks if a more recent update of the same market was already applied,
+    /// even if that market expired since.
+    fn is_stale(&self, update: &PriceUpdate) -> bool {
+        let market = ExchangeVertexPair::from(update);
+
+        self.markets
+            .get(&market)
+            .or_else(|| self.expired.get(&market))
+            .is_some_and(|last| last > update.timestamp())
+    }
+
+    /// Add the edges of a given collection of price updates to the graph,
+    /// including the edges connecting the same currency across exchanges.
+    /// The rates of every edge are net of trading fees and transfer costs.
+    fn insert_edges(
+        &mut self,
+        updates: impl IntoIterator<Item = PriceUpdate>,
+        repair: bool,
+    ) -> InsertReport {
+        let mut report = InsertReport::default();
+
         for update in updates {
+            if let Err(reason) = validate(&update) {
+                report.reject(update, reason);
+                continue;
+            }
+
+            if self.is_stale(&update) {
+                report.ignore(update);
+                continue;
+            }
+
             let (ex1, ex2) = self.insert_price_update(&update);
-            let src_node = self.graph.add_node(ex1);
-            let dst_node = self.graph.add_node(ex2);
+            let exchange = *update.exchange();
 
             self.add_edge(
-                src_node,
-                dst_node,
-                update.forward_factor(),
-                *update.backward_factor(),
+                ex1,
+                ex2,
+                self.fees.trade_rate(exchange, update.forward_factor()),
+                self.fees.trade_rate(exchange, *update.backward_factor()),
+                repair,
             );
+
+            report.apply(update);
         }
 
         self.currency_nodes().iter().for_each(|pair| {
-            self.add_edge(pair.0, pair.1, 1.into(), 1.into());
+            let forward_factor = self.fees.transfer_rate(pair.0, pair.1);
+            let backward_factor = self.fees.transfer_rate(pair.1, pair.0);
+            self.add_edge(pair.0, pair.1, forward_factor, backward_factor, repair);
         });
 
-        self.calculate_best_rates();
+        report
     }
 
     /// Add a price update to available exchange prices. If the price is not the
@@ -122,14 +838,33 @@ impl RateGraph {
 
         self.exchange_prices
             .entry(*ex1.currency())
-            .and_modify(|map| update_with_recent(map, ex1, &price.timestamp()))
+            .and_modify(|map| update_with_recent(map, ex1, price.timestamp()))
             .or_insert_with(|| HashMap::from_iter(vec![(ex1, *price.timestamp())]));
 
         self.exchange_prices
             .entry(*ex2.currency())
-            .and_modify(|map| update_with_recent(map, ex2, &price.timestamp()))
+            .and_modify(|map| update_with_recent(map, ex2, price.timestamp()))
             .or_insert_with(|| HashMap::from_iter(vec![(ex2, *price.timestamp())]));
 
+        self.markets.insert((ex1, ex2), *price.timestamp());
+        self.markets.insert((ex2, ex1), *price.timestamp());
+        self.expired.remove(&(ex1, ex2));
+        self.expired.remove(&(ex2, ex1));
+
+        if self.latest.is_none_or(|latest| latest < *price.timestamp()) {
+            self.latest = Some(*price.timestamp());
+        }
+
+        // A book from an older update no longer reflects the market.
+        self.books.remove(&(ex2, ex1));
+        self.prices.remove(&(ex2, ex1));
+        self.prices.insert((ex1, ex2), price.clone());
+        self.history.record(price);
+        match price.order_book() {
+            Some(book) => self.books.insert((ex1, ex2), book.clone()),
+            None => self.books.remove(&(ex1, ex2)),
+        };
+
         (ex1, ex2)
     }
 }
@@ -139,23 +874,29 @@ impl Default for RateGraph {
     fn default() -> Self {
         Self {
             exchange_prices: HashMap::new(),
+            markets: HashMap::new(),
+            books: HashMap::new(),
+            prices: HashMap::new(),
+            history: PriceHistory::default(),
+            expired: HashMap::new(),
+            suspended: HashSet::new(),
+            suspended_edges: HashMap::new(),
+            max_age: None,
+            latest: None,
             graph: DiGraphMap::new(),
             path: Path::default(),
+            cycles: vec![],
+            raised: vec![],
+            fees: FeeModel::default(),
         }
     }
 }
 
 impl From<Vec<PriceUpdate>> for RateGraph {
     /// Create a rate graph from a given vector of price updates.
-    /// Only updates with the latest timestamp are considered during graph creation.
+    /// Only updates with the latest timestamp are considered during graph creation,
+    /// and the best rates are calculated once all of them have been added.
     fn from(prices: Vec<PriceUpdate>) -> RateGraph {
-        let mut graph = Self {
-            exchange_prices: HashMap::new(),
-            graph: DiGraphMap::new(),
-            path: Path::default(),
-        };
-
-        graph.insert(prices);
-        graph
+        Self::default().rebuild(prices)
     }
 }
@@ -0,0 +1,120 @@
+use crate::{
+    exchange::{ExchangeVertex, ExchangeVertexPair},
+    input::PriceUpdate,
+};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, VecDeque};
+
+/// Number of price updates kept per market by default.
+pub const DEFAULT_HISTORY_DEPTH: usize = 16;
+
+/// An entry of the history of a market.
+#[derive(Debug, Clone)]
+enum Entry {
+    /// A price update applied to the market.
+    Update(PriceUpdate),
+
+    /// The removal of the market, at the time of the most recent update of
+    /// the graph then.
+    Removed(DateTime<Utc>),
+}
+
+/// A bounded history of the price updates applied to every market, from the
+/// oldest to the most recent.
+#[derive(Debug, Clone)]
+pub(crate) struct PriceHistory {
+    depth: usize,
+    markets: HashMap<ExchangeVertexPair, VecDeque<Entry>>,
+}
+
+impl PriceHistory {
+    /// Set the number of price updates kept per market, dropping the oldest
+    /// ones beyond it.
+    pub fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+
+        for updates in self.markets.values_mut() {
+            while updates.len() > depth {
+                updates.pop_front();
+            }
+        }
+
+        self.markets.retain(|_, updates| !updates.is_empty());
+    }
+
+    /// Record a price update applied to its market.
+    pub fn record(&mut self, update: &PriceUpdate) {
+        if self.depth == 0 {
+            return;
+        }
+
+        let (ex1, ex2) = ExchangeVertexPair::from(update);
+        self.push(market(ex1, ex2), Entry::Update(update.clone()));
+    }
+
+    /// Record the removal of a market at a given time, so it is left out from
+    /// that time on, while its earlier price updates are kept.
+    pub fn remove(&mut self, ex1: ExchangeVertex, ex2: ExchangeVertex, at: DateTime<Utc>) {
+        if self.depth == 0 {
+            return;
+        }
+
+        self.push(market(ex1, ex2), Entry::Removed(at));
+    }
+
+    /// Append an entry to the history of a market, dropping its oldest one
+    /// beyond the depth.
+    fn push(&mut self, market: ExchangeVertexPair, entry: Entry) {
+        let entries = self.markets.entry(market).or_default();
+
+        if entries.len() == self.depth {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// Gets the latest price update of every market whose timestamp isn't
+    /// after `as_of`, ordered by timestamp.
+    ///
+    /// Markets whose every recorded update is after `as_of`, or which were
+    /// removed by then, are left out.
+    pub fn as_of(&self, as_of: DateTime<Utc>) -> Vec<PriceUpdate> {
+        let mut updates: Vec<&PriceUpdate> = self
+            .markets
+            .values()
+            .filter_map(|entries| {
+                let entry = entries.iter().rev().find(|entry| match entry {
+                    Entry::Update(update) => *update.timestamp() <= as_of,
+                    Entry::Removed(at) => *at <= as_of,
+                });
+
+                match entry? {
+                    Entry::Update(update) => Some(update),
+                    Entry::Removed(_) => None,
+                }
+            })
+            .collect();
+
+        updates.sort_by_key(|update| (*update.timestamp(), ExchangeVertexPair::from(*update)));
+        updates.into_iter().cloned().collect()
+    }
+}
+
+/// Gets the key of a market, regardless of the direction of its updates.
+fn market(ex1: ExchangeVertex, ex2: ExchangeVertex) -> ExchangeVertexPair {
+    if ex1 < ex2 {
+        (ex1, ex2)
+    } else {
+        (ex2, ex1)
+    }
+}
+
+impl Default for PriceHistory {
+    // Create an empty history, keeping the default number of updates per market.
+    fn default() -> Self {
+        Self {
+            depth: DEFAULT_HISTORY_DEPTH,
+            markets: HashMap::new(),
+        }
+    }
+}
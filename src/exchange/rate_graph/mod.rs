@@ -1,5 +1,34 @@
+mod arbitrage;
+mod best_rate;
+mod constraints;
+mod dot;
 mod exchange_vertex;
+mod fees;
 mod graph;
+mod history;
 mod path;
+mod price_log;
+mod quote;
+mod report;
+mod routes;
+mod shared;
+mod snapshot;
+mod view;
 
-pub use self::{exchange_vertex::*, graph::*, path::*};
+pub use self::{
+    arbitrage::ArbitrageCycle,
+    best_rate::*,
+    constraints::RouteConstraints,
+    dot::DotOptions,
+    exchange_vertex::*,
+    fees::FeeModel,
+    graph::*,
+    history::DEFAULT_HISTORY_DEPTH,
+    path::*,
+    price_log::{Durable, LogError, LogRecord, PriceLog, LOG_VERSION},
+    quote::{Fill, Quote},
+    report::{InsertReport, UpdateRejection},
+    shared::SharedRateGraph,
+    snapshot::{SnapshotError, SNAPSHOT_VERSION},
+    view::RateView,
+};
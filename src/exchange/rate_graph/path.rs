@@ -5,7 +5,7 @@ use std::{fmt::Debug, ops::Mul};
 pub type FullPath<T> = Vec<T>;
 pub type PathCost<T> = T;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Path<V, E>
 where
     V: NodeTrait,
@@ -21,25 +21,221 @@ where
     E: NodeTrait + Debug + From<i32> + Copy + Add<Output = E>,
 {
     /// Gets the full most optimal path for moving from a given source node (`u`)
-    /// to a given destination node (`v`).
+    /// to a given destination node (`v`), along with its rate.
+    ///
+    /// Returns `None` if there is no such path, or if it goes around a
+    /// profitable cycle.
     pub fn full_path(&self, mut u: V, v: V) -> Option<(FullPath<V>, PathCost<E>)> {
         if !self.next.contains_edge(u, v) {
             return None;
         }
 
+        let path_cost = if u == v {
+            1.into()
+        } else {
+            *self.rate.edge_weight(u, v).unwrap()
+        };
+
         let mut path = vec![u];
-        let mut path_cost: E = 0.into();
         while u != v {
-            if let Some(node) = self.next.edge_weight(u, v).unwrap() {
-                path_cost = path_cost + *self.rate.edge_weight(u, v).unwrap();
-                u = *node;
-                path.push(u);
+            // No intermediate hop means there is no route between both nodes.
+            u = (*self.next.edge_weight(u, v).unwrap())?;
+
+            // Hops only repeat a node when they go around a profitable cycle,
+            // which would never reach the destination.
+            if path.contains(&u) {
+                return None;
             }
+            path.push(u);
         }
 
         Some((path, path_cost))
     }
 
+    /// Gets the best known rate for moving from a given source node (`u`)
+    /// to a given destination node (`v`).
+    pub fn rate(&self, u: V, v: V) -> Option<&E> {
+        self.rate.edge_weight(u, v)
+    }
+
+    /// Gets the node that immediately follows a given source node (`u`) on
+    /// the most optimal path to a given destination node (`v`).
+    pub fn next(&self, u: V, v: V) -> Option<V> {
+        self.next.edge_weight(u, v).cloned().unwrap_or(None)
+    }
+
+    /// Add a new node to the path graph, without any path connecting it to
+    /// the existing nodes.
+    pub fn insert_node<D>(&mut self, n: V, default: D)
+    where
+        D: Into<E> + Copy,
+    {
+        let nodes: Vec<V> = self.rate.nodes().chain(Some(n)).collect();
+
+        for x in nodes {
+            self.rate.add_edge(n, x, default.into());
+            self.rate.add_edge(x, n, default.into());
+            self.next.add_edge(n, x, None);
+            self.next.add_edge(x, n, None);
+        }
+    }
+
+    /// Remove a node from the path graph. No best path between the other
+    /// nodes must go through it anymore.
+    pub fn remove_node(&mut self, n: V) {
+        self.rate.remove_node(n);
+        self.next.remove_node(n);
+    }
+
+    /// Repair the best paths after the weight of a single edge (`u`, `v`) of
+    /// graph `g` changed from `old`, without recalculating every node triple.
+    ///
+    /// A missing edge (in `g` or as `old`) is treated as having the `default` weight.
+    pub fn update_edge<D>(&mut self, g: &DiGraphMap<V, E>, u: V, v: V, old: Option<E>, default: D)
+    where
+        E: Mul<Output = E> + Copy,
+        D: Into<E> + Copy,
+    {
+        let weight = g
+            .edge_weight(u, v)
+            .cloned()
+            .unwrap_or_else(|| default.into());
+        let old = old.unwrap_or_else(|| default.into());
+
+        if weight < old {
+            self.decrease_edge(g, u, v, old, default);
+        } else if weight > old {
+            self.increase_edge(u, v, weight);
+        }
+
+        // A direct edge is always a path, even when it doesn't improve the rate.
+        if g.contains_edge(u, v) && self.next(u, v).is_none() {
+            self.rate.add_edge(u, v, weight);
+            self.next.add_edge(u, v, Some(v));
+        }
+    }
+
+    /// Gets the rate of the best path between two nodes, counting a node as
+    /// reaching itself at no cost. Returns `None` if no such path exists.
+    fn reach(&self, u: V, v: V) -> Option<E> {
+        if u == v {
+            return Some(1.into());
+        }
+
+        self.next(u, v)
+            .map(|_| *self.rate.edge_weight(u, v).unwrap())
+    }
+
+    /// Relax every path through an edge (`u`, `v`) whose weight increased.
+    ///
+    /// Without profitable cycles, a best path uses the edge at most once, so it
+    /// is enough to join the best path into `u` with the best path out of `v`.
+    fn increase_edge(&mut self, u: V, v: V, weight: E)
+    where
+        E: Mul<Output = E> + Copy,
+    {
+        let nodes: Vec<V> = self.rate.nodes().collect();
+
+        let into_u: Vec<(V, E, V)> = nodes
+            .iter()
+            .filter_map(|&i| {
+                let hop = if i == u { Some(v) } else { self.next(i, u) };
+                Some((i, self.reach(i, u)?, hop?))
+            })
+            .collect();
+
+        let out_of_v: Vec<(V, E)> = nodes
+            .iter()
+            .filter_map(|&j| Some((j, self.reach(v, j)?)))
+            .collect();
+
+        for &(i, i_u, hop) in into_u.iter() {
+            for &(j, v_j) in out_of_v.iter() {
+                let total = i_u * weight * v_j;
+
+                if *self.rate.edge_weight(i, j).unwrap() < total {
+                    self.rate.add_edge(i, j, total);
+                    self.next.add_edge(i, j, Some(hop));
+                }
+            }
+        }
+    }
+
+    /// Recalculate the best paths of every source node that may have routed
+    /// through an edge (`u`, `v`) whose weight decreased from `old`.
+    fn decrease_edge<D>(&mut self, g: &DiGraphMap<V, E>, u: V, v: V, old: E, default: D)
+    where
+        E: Mul<Output = E> + Copy,
+        D: Into<E> + Copy,
+    {
+        let sources: Vec<V> = self
+            .rate
+            .nodes()
+            .filter(|&i| match self.reach(i, u) {
+                None => false,
+                Some(i_u) => self.rate.nodes().any(|j| match self.reach(v, j) {
+                    None => false,
+                    Some(v_j) => *self.rate.edge_weight(i, j).unwrap() <= i_u * old * v_j,
+                }),
+            })
+            .collect();
+
+        for source in sources {
+            self.bellman_ford(g, source, default);
+        }
+    }
+
+    /// Recalculate the best paths from a single `source` node to every
+    /// other node using the Bellman Ford's algorithm.
+    fn bellman_ford<D>(&mut self, g: &DiGraphMap<V, E>, source: V, default: D)
+    where
+        E: Mul<Output = E> + Copy,
+        D: Into<E> + Copy,
+    {
+        let nodes: Vec<V> = self.rate.nodes().collect();
+
+        for &j in nodes.iter() {
+            match g.edge_weight(source, j) {
+                Some(w) => {
+                    self.rate.add_edge(source, j, *w);
+                    self.next.add_edge(source, j, Some(j));
+                }
+                None => {
+                    self.rate.add_edge(source, j, default.into());
+                    self.next.add_edge(source, j, None);
+                }
+            }
+        }
+
+        // Without profitable cycles, every best path is found after at most
+        // one relaxation round per node.
+        for _ in 0..nodes.len() {
+            let mut relaxed = false;
+
+            for (x, y, w) in g.edge_references() {
+                if x == source {
+                    continue;
+                }
+
+                let hop = match self.next(source, x) {
+                    Some(hop) => hop,
+                    None => continue,
+                };
+
+                let total = *self.rate.edge_weight(source, x).unwrap() * *w;
+                if *self.rate.edge_weight(source, y).unwrap() < total {
+                    self.rate.add_edge(source, y, total);
+                    self.next.add_edge(source, y, Some(hop));
+                    relaxed = true;
+                }
+            }
+
+            if !relaxed {
+                break;
+            }
+        }
+    }
+
     /// Populate the path graph with default values, by using the
     /// nodes of an existing directional graph.
     fn populate_from_graph<D>(&mut self, g: &DiGraphMap<V, E>, default: D)
@@ -0,0 +1,427 @@
+use crate::{
+    exchange::{Currency, ExchangeType, FeeModel},
+    input::{
+        line::{
+            currency, decimal, exchange, format_price_update, price_update, seconds, timestamp,
+        },
+        LineParseError, OrderBook, PriceLevel, PriceUpdate,
+    },
+    utils::{str_utils::tokens, time_utils::TIMESTAMP_FORMAT},
+};
+use chrono::{DateTime, Duration, Utc};
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+/// Number of fields of a price update, at the start of every record.
+const UPDATE_FIELDS: usize = 7;
+
+/// Header of the first line of every log, followed by its version.
+const LOG_HEADER: &str = "PRICE_LOG";
+
+/// Version of the logs written, and the only version read. Logs without any
+/// header were written before price updates were quoted at their bid and ask,
+/// and before other changes were logged.
+pub const LOG_VERSION: u32 = 2;
+
+/// Errors that can occur while replaying a log of price updates.
+#[derive(Debug)]
+pub enum LogError {
+    Io(io::Error),
+    MissingHeader,
+    UnsupportedVersion(String),
+    InvalidRecord(usize, LineParseError),
+}
+
+/// A change to a rate graph, as recorded in a `PriceLog`.
+#[derive(Debug, Clone)]
+pub enum LogRecord {
+    /// A price update, see `RateGraph::insert`.
+    Update(PriceUpdate),
+
+    /// The removal of a market, see `RateGraph::remove_market`.
+    RemoveMarket(ExchangeType, Currency, Currency),
+
+    /// The suspension of an exchange, see `RateGraph::suspend_exchange`.
+    Suspend(ExchangeType),
+
+    /// The resumption of an exchange, see `RateGraph::resume_exchange`.
+    Resume(ExchangeType),
+
+    /// The expiry of old markets, see `RateGraph::expire_before`.
+    ExpireBefore(DateTime<Utc>),
+
+    /// A new maximum market age, see `RateGraph::set_max_age`.
+    MaxAge(Option<Duration>),
+
+    /// New fees, see `RateGraph::set_fees`.
+    Fees(FeeModel),
+}
+
+/// A writer whose data can be made durable once written, e.g. a file synced
+/// to its storage device.
+pub trait Durable: Write {
+    /// Make the data written so far durable.
+    fn sync(&mut self) -> io::Result<()>;
+}
+
+/// An append-only, write-ahead log of the changes made to a rate graph.
+///
+/// Every record is a single line. A price update is written as
+/// `<timestamp> <exchange> <source> <destination> <bid> <ask> <backward_factor>`,
+/// followed by the `LAST <price>` of its last trade and the `BID <price> <size>` and `ASK <price> <size>` levels of
+/// its order book, if any. Any other change starts with its kind:
+///
+/// - `REMOVE_MARKET <exchange> <base> <quote>`
+/// - `SUSPEND <exchange>` and `RESUME <exchange>`
+/// - `EXPIRE_BEFORE <timestamp>`
+/// - `MAX_AGE <seconds>`, or `MAX_AGE NONE`
+/// - `FEES`, followed by every `TRADING_FEE <exchange> <fee>`,
+///   `WITHDRAWAL_FEE <currency> <fee>` and `TRANSFER_FACTOR <source> <destination> <factor>`
+///
+/// Records follow a `PRICE_LOG <version>` header, written along with the
+/// first of them. They are synced before being applied, so every change made
+/// to the graph can be replayed after a crash. A record only counts once its
+/// line is terminated, so a record cut short by a crash is ignored.
+#[derive(Debug)]
+pub struct PriceLog<W: Durable> {
+    writer: W,
+    started: bool,
+}
+
+impl Durable for File {
+    /// Sync the content of the file, without its metadata.
+    fn sync(&mut self) -> io::Result<()> {
+        self.sync_data()
+    }
+}
+
+impl Durable for Vec<u8> {
+    /// Data written in memory is never durable, so there is nothing to sync.
+    fn sync(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl PriceLog<File> {
+    /// Open a log file for appending, creating it if it doesn't exist.
+    ///
+    /// A truncated final record, left by a crash in the middle of a write, is
+    /// removed so that new records don't get appended to it. Logs written with
+    /// another version of the format are rejected.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+
+        let mut complete = 0;
+        let mut reader = BufReader::new(&file);
+        let mut line = vec![];
+
+        loop {
+            line.clear();
+            let read = reader.read_until(b'\n', &mut line)?;
+
+            if read == 0 || line.last() != Some(&b'\n') {
+                break;
+            }
+
+            if complete == 0 {
+                let header = String::from_utf8_lossy(&line);
+                check_header(&header)
+                    .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+            }
+
+            complete += read as u64;
+        }
+
+        file.set_len(complete)?;
+        file.seek(SeekFrom::End(0))?;
+        Ok(Self {
+            writer: file,
+            started: complete > 0,
+        })
+    }
+}
+
+impl<W: Durable> PriceLog<W> {
+    /// Create a new log, writing its header and records to the specified
+    /// writer, which is expected to be empty.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            started: false,
+        }
+    }
+
+    /// Append a price update to the log, and sync it.
+    pub fn append(&mut self, update: &PriceUpdate) -> io::Result<()> {
+        self.append_records(&[LogRecord::Update(update.clone())])
+    }
+
+    /// Append a batch of records to the log, and sync them once they are all
+    /// written. The records must be appended before being applied to the graph.
+    pub fn append_records<'a>(
+        &mut self,
+        records: impl IntoIterator<Item = &'a LogRecord>,
+    ) -> io::Result<()> {
+        if !self.started {
+            writeln!(self.writer, "{} {}", LOG_HEADER, LOG_VERSION)?;
+            self.started = true;
+        }
+
+        for record in records {
+            // The record is written at once, so it is either complete or truncated.
+            let mut line = format_record(record);
+            line.push('\n');
+            self.writer.write_all(line.as_bytes())?;
+        }
+
+        self.writer.flush()?;
+        self.writer.sync()
+    }
+
+    /// Gets back the writer of the log.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Read the records of a log, in the order they were appended, up to the
+/// first price update whose timestamp is after `as_of`, or expiry whose cutoff
+/// is. Other changes have no timestamp, so every change appended from then on
+/// is left out, as it was made after `as_of`.
+///
+/// An empty log has no record. A truncated final record is ignored, while a
+/// missing header, any other version, or any other invalid record fails.
+pub(crate) fn read_log(
+    mut reader: impl BufRead,
+    as_of: Option<DateTime<Utc>>,
+) -> Result<Vec<LogRecord>, LogError> {
+    let mut records = vec![];
+    let mut line = String::new();
+
+    if reader.read_line(&mut line)? == 0 || !line.ends_with('\n') {
+        return Ok(records);
+    }
+    check_header(&line)?;
+
+    for line_number in 2.. {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 || !line.ends_with('\n') {
+            break;
+        }
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let record =
+            parse_record(&line).map_err(|error| LogError::InvalidRecord(line_number, error))?;
+        let timestamp = match &record {
+            LogRecord::Update(update) => Some(*update.timestamp()),
+            LogRecord::ExpireBefore(cutoff) => Some(*cutoff),
+            _ => None,
+        };
+
+        if timestamp
+            .zip(as_of)
+            .is_some_and(|(timestamp, as_of)| timestamp > as_of)
+        {
+            break;
+        }
+
+        records.push(record);
+    }
+
+    Ok(records)
+}
+
+/// Checks that the header of a log is of the version written.
+fn check_header(header: &str) -> Result<(), LogError> {
+    match header.split_whitespace().collect::<Vec<_>>().as_slice() {
+        [LOG_HEADER, version] if *version == LOG_VERSION.to_string() => Ok(()),
+        [LOG_HEADER, version] => Err(LogError::UnsupportedVersion(version.to_string())),
+        _ => Err(LogError::MissingHeader),
+    }
+}
+
+/// Format a single record of a log, without its line break.
+fn format_record(record: &LogRecord) -> String {
+    match record {
+        LogRecord::Update(update) => {
+            let mut line = format_price_update(update);
+
+            if let Some(last) = update.last() {
+                line += &format!(" LAST {}", last);
+            }
+            if let Some(book) = update.order_book() {
+                for level in book.bids() {
+                    line += &format!(" BID {} {}", level.price(), level.size());
+                }
+                for level in book.asks() {
+                    line += &format!(" ASK {} {}", level.price(), level.size());
+                }
+            }
+
+            line
+        }
+        LogRecord::RemoveMarket(exchange, base, quote) => {
+            format!("REMOVE_MARKET {} {} {}", exchange, base, quote)
+        }
+        LogRecord::Suspend(exchange) => format!("SUSPEND {}", exchange),
+        LogRecord::Resume(exchange) => format!("RESUME {}", exchange),
+        LogRecord::ExpireBefore(cutoff) => {
+            format!("EXPIRE_BEFORE {}", cutoff.format(TIMESTAMP_FORMAT))
+        }
+        LogRecord::MaxAge(Some(max_age)) => format!("MAX_AGE {}", max_age.num_seconds()),
+        LogRecord::MaxAge(None) => "MAX_AGE NONE".to_string(),
+        LogRecord::Fees(fees) => {
+            let mut line = "FEES".to_string();
+
+            let mut trading_fees: Vec<_> = fees.trading_fees.iter().collect();
+            trading_fees.sort();
+            for (exchange, fee) in trading_fees {
+                line += &format!(" TRADING_FEE {} {}", exchange, fee);
+            }
+
+            let mut withdrawal_fees: Vec<_> = fees.withdrawal_fees.iter().collect();
+            withdrawal_fees.sort();
+            for (currency, fee) in withdrawal_fees {
+                line += &format!(" WITHDRAWAL_FEE {} {}", currency, fee);
+            }
+
+            let mut transfer_factors: Vec<_> = fees.transfer_factors.iter().collect();
+            transfer_factors.sort();
+            for ((source, destination), factor) in transfer_factors {
+                line += &format!(" TRANSFER_FACTOR {} {} {}", source, destination, factor);
+            }
+
+            line
+        }
+    }
+}
+
+/// Parse a single record of a log.
+fn parse_record(record: &str) -> Result<LogRecord, LineParseError> {
+    let fields = tokens(record);
+
+    match fields.as_slice() {
+        [(_, "REMOVE_MARKET"), exchange_type, base, quote] => Ok(LogRecord::RemoveMarket(
+            exchange(*exchange_type)?,
+            currency(*base)?,
+            currency(*quote)?,
+        )),
+        [(_, "SUSPEND"), exchange_type] => Ok(LogRecord::Suspend(exchange(*exchange_type)?)),
+        [(_, "RESUME"), exchange_type] => Ok(LogRecord::Resume(exchange(*exchange_type)?)),
+        [(_, "EXPIRE_BEFORE"), cutoff] => Ok(LogRecord::ExpireBefore(timestamp(*cutoff)?)),
+        [(_, "MAX_AGE"), (_, "NONE")] => Ok(LogRecord::MaxAge(None)),
+        [(_, "MAX_AGE"), max_age] => Ok(LogRecord::MaxAge(Some(seconds(*max_age)?))),
+        [(_, "FEES"), entries @ ..] => parse_fees(entries).map(LogRecord::Fees),
+        _ => parse_update(&fields).map(LogRecord::Update),
+    }
+}
+
+/// Parse the entries of a fees record.
+fn parse_fees(mut entries: &[(usize, &str)]) -> Result<FeeModel, LineParseError> {
+    let mut fees = FeeModel::default();
+
+    while !entries.is_empty() {
+        entries = match entries {
+            [(_, "TRADING_FEE"), exchange_type, fee, rest @ ..] => {
+                fees = fees.with_trading_fee(exchange(*exchange_type)?, decimal(*fee)?);
+                rest
+            }
+            [(_, "WITHDRAWAL_FEE"), symbol, fee, rest @ ..] => {
+                fees = fees.with_withdrawal_fee(currency(*symbol)?, decimal(*fee)?);
+                rest
+            }
+            [(_, "TRANSFER_FACTOR"), source, destination, factor, rest @ ..] => {
+                fees = fees.with_transfer_factor(
+                    exchange(*source)?,
+                    exchange(*destination)?,
+                    decimal(*factor)?,
+                );
+                rest
+            }
+            _ => return Err(LineParseError::UnknownLine),
+        };
+    }
+
+    Ok(fees)
+}
+
+/// Parse a price update record, along with its last trade and order book.
+fn parse_update(fields: &[(usize, &str)]) -> Result<PriceUpdate, LineParseError> {
+    if fields.len() < UPDATE_FIELDS {
+        return Err(LineParseError::UnknownLine);
+    }
+
+    let mut update = price_update(&fields[..UPDATE_FIELDS])?;
+    let mut levels = &fields[UPDATE_FIELDS..];
+
+    if let [(_, "LAST"), last, rest @ ..] = levels {
+        update = update.with_last(decimal(*last)?);
+        levels = rest;
+    }
+
+    if levels.is_empty() {
+        return Ok(update);
+    }
+
+    if !levels.len().is_multiple_of(3) {
+        return Err(LineParseError::UnknownLine);
+    }
+
+    let (mut bids, mut asks) = (vec![], vec![]);
+    for level in levels.chunks(3) {
+        let price_level = PriceLevel::new(decimal(level[1])?, decimal(level[2])?);
+
+        match level[0].1 {
+            "BID" => bids.push(price_level),
+            "ASK" => asks.push(price_level),
+            _ => return Err(LineParseError::UnknownLine),
+        }
+    }
+
+    Ok(update.with_order_book(OrderBook::new(bids, asks)))
+}
+
+impl Display for LogError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LogError::Io(_) => write!(f, "failed to read price log"),
+            LogError::MissingHeader => write!(f, "missing price log header"),
+            LogError::UnsupportedVersion(version) => {
+                write!(f, "unsupported price log version `{}`", version)
+            }
+            LogError::InvalidRecord(line, error) => {
+                write!(f, "invalid price log record on line {}: {}", line, error)
+            }
+        }
+    }
+}
+
+impl Error for LogError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LogError::Io(error) => Some(error),
+            LogError::InvalidRecord(_, error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for LogError {
+    /// Convert from `io::Error` to `LogError`.
+    fn from(error: io::Error) -> Self {
+        LogError::Io(error)
+    }
+}
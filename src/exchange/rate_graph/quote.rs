@@ -0,0 +1,209 @@
+use crate::{
+    exchange::{
+        rate_graph::routes::k_best_routes, ExchangeVertex, ExchangeVertexPair, FeeModel,
+        RateGraphError,
+    },
+    input::{ExchangeRateRequest, OrderBook},
+};
+use petgraph::graphmap::DiGraphMap;
+use rust_decimal::Decimal;
+use std::{cmp::Reverse, collections::HashMap};
+
+/// Maximum number of routes an amount can be split across, picked by their top-of-book rate.
+const MAX_ROUTES: usize = 8;
+
+/// Number of equal slices an amount is divided into, before each slice is
+/// allocated to the route giving the most for it.
+const QUOTE_SLICES: u32 = 100;
+
+/// The part of a quoted amount converted along a single route.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fill {
+    route: Vec<ExchangeVertex>,
+    amount: Decimal,
+    received: Decimal,
+}
+
+/// The result of converting a concrete amount for an exchange rate request,
+/// walking the depth of every market on the way.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Quote {
+    request: ExchangeRateRequest,
+    amount: Decimal,
+    received: Decimal,
+    fills: Vec<Fill>,
+}
+
+impl Fill {
+    /// Gets the vertices of the route, from the source to the destination.
+    pub fn route(&self) -> &[ExchangeVertex] {
+        &self.route
+    }
+
+    /// Gets the amount of the source currency sent along the route.
+    pub fn amount(&self) -> Decimal {
+        self.amount
+    }
+
+    /// Gets the amount of the destination currency received from the route.
+    pub fn received(&self) -> Decimal {
+        self.received
+    }
+}
+
+impl Quote {
+    /// Gets the exchange rate request that was quoted.
+    pub fn request(&self) -> &ExchangeRateRequest {
+        &self.request
+    }
+
+    /// Gets the quoted amount of the source currency.
+    pub fn amount(&self) -> Decimal {
+        self.amount
+    }
+
+    /// Gets the total amount of the destination currency received.
+    pub fn received(&self) -> Decimal {
+        self.received
+    }
+
+    /// Gets the effective rate of the whole amount.
+    pub fn rate(&self) -> Decimal {
+        self.received / self.amount
+    }
+
+    /// Gets the routes the amount was split across, from the largest part.
+    pub fn fills(&self) -> &[Fill] {
+        &self.fills
+    }
+}
+
+/// Everything needed to convert amounts along the routes of a rate graph.
+pub(crate) struct Market<'a> {
+    pub graph: &'a DiGraphMap<ExchangeVertex, Decimal>,
+    pub books: &'a HashMap<ExchangeVertexPair, OrderBook>,
+    pub fees: &'a FeeModel,
+}
+
+/// The amount of input already taken from each side of every order book.
+type Consumed = HashMap<ExchangeVertexPair, Decimal>;
+
+impl<'a> Market<'a> {
+    /// Quote a given amount for an exchange rate request, by splitting it
+    /// across the routes giving the most of the destination currency.
+    pub fn quote(
+        &self,
+        request: &ExchangeRateRequest,
+        amount: Decimal,
+    ) -> Result<Quote, RateGraphError> {
+        let zero = Decimal::from(0);
+        if amount <= zero {
+            return Err(RateGraphError::NonPositiveAmount(amount));
+        }
+
+        let (u, v) = ExchangeVertexPair::from(request);
+        let routes: Vec<Vec<ExchangeVertex>> = k_best_routes(self.graph, u, v, MAX_ROUTES)
+            .into_iter()
+            .map(|(route, _)| route)
+            .collect();
+
+        if routes.is_empty() {
+            return Err(RateGraphError::NoEdgesBetweenNodes(u, v));
+        }
+
+        let slice = amount / Decimal::from(QUOTE_SLICES);
+        let mut consumed = Consumed::new();
+        let mut fills: Vec<Fill> = routes
+            .into_iter()
+            .map(|route| Fill {
+                route,
+                amount: zero,
+                received: zero,
+            })
+            .collect();
+
+        for index in 0..QUOTE_SLICES {
+            // The last slice takes whatever is left after rounding.
+            let slice = if index == QUOTE_SLICES - 1 {
+                amount - slice * Decimal::from(QUOTE_SLICES - 1)
+            } else {
+                slice
+            };
+
+            let best = fills
+                .iter()
+                .enumerate()
+                .filter_map(|(i, fill)| {
+                    let charge_fees = fill.amount == zero;
+                    let (received, taken) =
+                        self.convert(&fill.route, slice, &consumed, charge_fees)?;
+                    Some((i, received, taken))
+                })
+                .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)));
+
+            let (i, received, taken) = best.ok_or(RateGraphError::InsufficientLiquidity(u, v))?;
+            for (market, input) in taken {
+                *consumed.entry(market).or_insert(zero) += input;
+            }
+
+            fills[i].amount += slice;
+            fills[i].received += received;
+        }
+
+        fills.retain(|fill| fill.amount > zero);
+        fills.sort_by_key(|fill| Reverse(fill.amount));
+
+        Ok(Quote {
+            request: request.clone(),
+            amount,
+            received: fills.iter().fold(zero, |total, fill| total + fill.received),
+            fills,
+        })
+    }
+
+    /// Convert an amount along a route, walking the order book of every trade
+    /// which has one, and using the rate of the graph otherwise.
+    ///
+    /// Returns the amount received along with the input taken from each book,
+    /// or `None` if a book isn't deep enough.
+    fn convert(
+        &self,
+        route: &[ExchangeVertex],
+        mut amount: Decimal,
+        consumed: &Consumed,
+        charge_fees: bool,
+    ) -> Option<(Decimal, Vec<(ExchangeVertexPair, Decimal)>)> {
+        let zero = Decimal::from(0);
+        let mut taken = vec![];
+
+        for pair in route.windows(2) {
+            let (src, dst) = (pair[0], pair[1]);
+
+            if src.exchange() != dst.exchange() {
+                if charge_fees {
+                    amount = (amount - self.fees.withdrawal_fee(*src.currency())).max(zero);
+                }
+
+                amount *= *self.graph.edge_weight(src, dst).unwrap();
+                continue;
+            }
+
+            let used = consumed.get(&(src, dst)).cloned().unwrap_or(zero);
+            let received = match (self.books.get(&(src, dst)), self.books.get(&(dst, src))) {
+                (Some(book), _) => book.sell(used, amount)?,
+                (_, Some(book)) => book.buy(used, amount)?,
+                (None, None) => {
+                    amount *= *self.graph.edge_weight(src, dst).unwrap();
+                    continue;
+                }
+            };
+
+            taken.push(((src, dst), amount));
+            amount = self.fees.trade_rate(*src.exchange(), received);
+        }
+
+        Some((amount, taken))
+    }
+}
@@ -0,0 +1,95 @@
+use crate::input::PriceUpdate;
+use rust_decimal::Decimal;
+use std::{
+    error::Error,
+    fmt::{self, Display},
+};
+
+/// The reasons a price update can be rejected by a rate graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UpdateRejection {
+    /// The source and destination currencies of the update are the same.
+    SameCurrency,
+
+    /// A factor or the ask of the update is zero or negative.
+    NonPositiveFactor,
+}
+
+impl Display for UpdateRejection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UpdateRejection::SameCurrency => {
+                write!(
+                    f,
+                    "price update has the same source and destination currency"
+                )
+            }
+            UpdateRejection::NonPositiveFactor => {
+                write!(f, "price update has a factor which isn't positive")
+            }
+        }
+    }
+}
+
+impl Error for UpdateRejection {}
+
+/// The outcome of inserting a batch of price updates into a rate graph.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InsertReport {
+    applied: Vec<PriceUpdate>,
+    stale: Vec<PriceUpdate>,
+    rejected: Vec<(PriceUpdate, UpdateRejection)>,
+}
+
+impl InsertReport {
+    /// Gets the updates whose rates were applied to the graph.
+    pub fn applied(&self) -> &[PriceUpdate] {
+        &self.applied
+    }
+
+    /// Gets the updates ignored because a more recent update of the same
+    /// market was already applied.
+    pub fn stale(&self) -> &[PriceUpdate] {
+        &self.stale
+    }
+
+    /// Gets the updates rejected as invalid, along with the reason.
+    pub fn rejected(&self) -> &[(PriceUpdate, UpdateRejection)] {
+        &self.rejected
+    }
+
+    pub(crate) fn apply(&mut self, update: PriceUpdate) {
+        self.applied.push(update);
+    }
+
+    pub(crate) fn ignore(&mut self, update: PriceUpdate) {
+        self.stale.push(update);
+    }
+
+    pub(crate) fn reject(&mut self, update: PriceUpdate, reason: UpdateRejection) {
+        self.rejected.push((update, reason));
+    }
+
+    pub(crate) fn extend(&mut self, other: InsertReport) {
+        self.applied.extend(other.applied);
+        self.stale.extend(other.stale);
+        self.rejected.extend(other.rejected);
+    }
+}
+
+/// Checks if a price update describes a valid market.
+pub(crate) fn validate(update: &PriceUpdate) -> Result<(), UpdateRejection> {
+    if update.source_currency() == update.destination_currency() {
+        return Err(UpdateRejection::SameCurrency);
+    }
+
+    let zero = Decimal::from(0);
+    if update.forward_factor() <= zero || update.ask() <= zero || *update.backward_factor() <= zero
+    {
+        return Err(UpdateRejection::NonPositiveFactor);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,146 @@
+use crate::exchange::ExchangeVertex;
+use petgraph::graphmap::DiGraphMap;
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+
+/// A loop-free sequence of vertices, along with the product of its rates.
+type Route = (Vec<ExchangeVertex>, Decimal);
+
+/// Find up to `k` loop-free routes between two vertices, from the best rate,
+/// using the Yen's algorithm.
+///
+/// The graph is expected to have no profitable cycle between both vertices,
+/// otherwise the best route to deviate from may not be found.
+pub(crate) fn k_best_routes(
+    graph: &DiGraphMap<ExchangeVertex, Decimal>,
+    u: ExchangeVertex,
+    v: ExchangeVertex,
+    k: usize,
+) -> Vec<Route> {
+    let mut found: Vec<Route> = vec![];
+    let mut candidates: Vec<Route> = vec![];
+
+    if k == 0 {
+        return found;
+    }
+
+    match best_route(graph, u, v, &HashSet::new(), &HashSet::new()) {
+        Some(route) => found.push(route),
+        None => return found,
+    }
+
+    while found.len() < k {
+        let (last, _) = found.last().unwrap().clone();
+
+        for i in 0..last.len() - 1 {
+            let root = &last[..=i];
+
+            // Deviate from every route sharing the same root at its spur vertex.
+            let removed_edges: HashSet<(ExchangeVertex, ExchangeVertex)> = found
+                .iter()
+                .filter(|(route, _)| route.len() > i + 1 && &route[..=i] == root)
+                .map(|(route, _)| (route[i], route[i + 1]))
+                .collect();
+            let removed_nodes: HashSet<ExchangeVertex> = root[..i].iter().cloned().collect();
+
+            let spur = match best_route(graph, last[i], v, &removed_nodes, &removed_edges) {
+                Some((spur, _)) => spur,
+                None => continue,
+            };
+
+            let route: Vec<ExchangeVertex> = root[..i].iter().cloned().chain(spur).collect();
+            let known = |(other, _): &Route| *other == route;
+            if !found.iter().any(known) && !candidates.iter().any(known) {
+                let rate = rate(graph, &route);
+                candidates.push((route, rate));
+            }
+        }
+
+        // Ties are broken by the fewest hops, then by discovery order.
+        let best = candidates
+            .iter()
+            .enumerate()
+            .max_by(|(i, a), (j, b)| a.1.cmp(&b.1).then(b.0.len().cmp(&a.0.len())).then(j.cmp(i)))
+            .map(|(i, _)| i);
+
+        match best {
+            Some(i) => found.push(candidates.remove(i)),
+            None => break,
+        }
+    }
+
+    found
+}
+
+/// Gets the product of the rates along a route.
+fn rate(graph: &DiGraphMap<ExchangeVertex, Decimal>, route: &[ExchangeVertex]) -> Decimal {
+    route.windows(2).fold(Decimal::from(1), |rate, pair| {
+        rate * *graph.edge_weight(pair[0], pair[1]).unwrap()
+    })
+}
+
+/// Find the route with the best rate between two vertices using the Bellman
+/// Ford's algorithm, without going through any of the removed nodes or edges.
+fn best_route(
+    graph: &DiGraphMap<ExchangeVertex, Decimal>,
+    u: ExchangeVertex,
+    v: ExchangeVertex,
+    removed_nodes: &HashSet<ExchangeVertex>,
+    removed_edges: &HashSet<(ExchangeVertex, ExchangeVertex)>,
+) -> Option<Route> {
+    if !graph.contains_node(u) || !graph.contains_node(v) {
+        return None;
+    }
+
+    let mut best: HashMap<ExchangeVertex, Decimal> = HashMap::new();
+    let mut predecessor: HashMap<ExchangeVertex, ExchangeVertex> = HashMap::new();
+    best.insert(u, 1.into());
+
+    for _ in 0..graph.node_count() {
+        let mut relaxed = false;
+
+        for (x, y, w) in graph.all_edges() {
+            if x == y
+                || y == u
+                || *w <= Decimal::from(0)
+                || removed_nodes.contains(&x)
+                || removed_nodes.contains(&y)
+                || removed_edges.contains(&(x, y))
+            {
+                continue;
+            }
+
+            let total = match best.get(&x) {
+                Some(rate) => *rate * *w,
+                None => continue,
+            };
+
+            if best.get(&y).is_none_or(|rate| *rate < total) {
+                best.insert(y, total);
+                predecessor.insert(y, x);
+                relaxed = true;
+            }
+        }
+
+        if !relaxed {
+            break;
+        }
+    }
+
+    let rate = *best.get(&v)?;
+    let mut route = vec![v];
+
+    while *route.last().unwrap() != u {
+        let previous = predecessor[route.last().unwrap()];
+
+        // A vertex seen twice means the predecessors went through a profitable cycle.
+        if route.contains(&previous) {
+            return None;
+        }
+
+        route.push(previous);
+    }
+
+    route.reverse();
+    Some((route, rate))
+}
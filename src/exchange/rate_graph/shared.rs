@@ -0,0 +1,73 @@
+use crate::{
+    exchange::{BestRate, InsertReport, RateGraph, RateGraphError, RateView},
+    input::{ExchangeRateRequest, PriceUpdate},
+};
+use arc_swap::ArcSwap;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// A rate graph shared across threads, where readers never wait on writers.
+///
+/// Readers query an immutable snapshot of the current rates (see `RateView`),
+/// which is atomically swapped for a new one once a writer has applied its
+/// updates. Writers are serialized among themselves, and readers holding an
+/// older snapshot keep using it until they load the next one.
+///
+/// Only the view is copied on every change, never the history, order books
+/// or price updates of the rate graph kept by the writer.
+#[derive(Debug)]
+pub struct SharedRateGraph {
+    writer: Mutex<RateGraph>,
+    snapshot: ArcSwap<RateView>,
+}
+
+impl SharedRateGraph {
+    /// Create a new shared rate graph, starting from an existing rate graph.
+    pub fn new(rate_graph: RateGraph) -> Self {
+        Self {
+            snapshot: ArcSwap::from_pointee(rate_graph.view()),
+            writer: Mutex::new(rate_graph),
+        }
+    }
+
+    /// Gets the most recently published snapshot of the rate graph.
+    pub fn snapshot(&self) -> Arc<RateView> {
+        self.snapshot.load_full()
+    }
+
+    /// Returns the best rate of a request, according to the most recently
+    /// published snapshot of the rate graph.
+    pub fn full_path(&self, request: &ExchangeRateRequest) -> Result<BestRate, RateGraphError> {
+        self.snapshot.load().full_path(request)
+    }
+
+    /// Insert a given collection of price updates, and publish the resulting
+    /// rate graph to readers.
+    pub fn insert(&self, updates: impl IntoIterator<Item = PriceUpdate>) -> InsertReport {
+        self.update(|rate_graph| rate_graph.insert(updates))
+    }
+
+    /// Apply any change to the rate graph, and publish the result to readers.
+    ///
+    /// A change which panics publishes nothing, and doesn't stop later changes:
+    /// they are applied to the rate graph as the panicking change left it, and
+    /// publish it along with their own result.
+    pub fn update<T>(&self, change: impl FnOnce(&mut RateGraph) -> T) -> T {
+        let mut rate_graph = self.writer.lock().unwrap_or_else(PoisonError::into_inner);
+        let result = change(&mut rate_graph);
+
+        self.snapshot.store(Arc::new(rate_graph.view()));
+        result
+    }
+}
+
+impl Default for SharedRateGraph {
+    fn default() -> Self {
+        Self::new(RateGraph::default())
+    }
+}
+
+impl From<RateGraph> for SharedRateGraph {
+    fn from(rate_graph: RateGraph) -> Self {
+        Self::new(rate_graph)
+    }
+}
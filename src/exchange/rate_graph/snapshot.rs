@@ -0,0 +1,286 @@
+use crate::{
+    exchange::{Currency, ExchangeType, ExchangeVertex, ExchangeVertexPair, FeeModel},
+    input::{
+        line::{
+            currency, decimal, exchange, format_price_update, price_update, seconds, timestamp,
+        },
+        LineParseError, OrderBook, PriceLevel, PriceUpdate,
+    },
+    utils::{str_utils::tokens, time_utils::TIMESTAMP_FORMAT},
+};
+use chrono::{DateTime, Duration, Utc};
+use std::{
+    collections::BTreeSet,
+    error::Error,
+    fmt::{self, Display},
+    io::{self, BufRead, Write},
+};
+
+/// Header of the first line of every snapshot, followed by its version.
+const SNAPSHOT_HEADER: &str = "RATE_GRAPH_SNAPSHOT";
+
+/// Version of the snapshots written, and the only version read. Snapshots of
+/// the first version quoted price updates at normalized factors rather than
+/// at their bid and ask, and didn't keep expired markets.
+pub const SNAPSHOT_VERSION: u32 = 2;
+
+/// Errors that can occur while loading a snapshot of a rate graph.
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(io::Error),
+    MissingHeader,
+    UnsupportedVersion(String),
+    InvalidEntry(usize, LineParseError),
+}
+
+/// The state of a rate graph which can't be recalculated from anything else.
+#[derive(Debug, Default)]
+pub(crate) struct Snapshot {
+    pub fees: FeeModel,
+    pub suspended: Vec<ExchangeType>,
+    pub max_age: Option<Duration>,
+    pub latest: Option<DateTime<Utc>>,
+    pub expired: Vec<(ExchangeVertexPair, DateTime<Utc>)>,
+    pub prices: Vec<PriceUpdate>,
+}
+
+impl Snapshot {
+    /// Write the snapshot, one entry per line, after the header and version.
+    ///
+    /// Every currency and exchange used is registered first, so a snapshot can
+    /// be restored by a process which doesn't know about them yet.
+    pub fn write(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "{} {}", SNAPSHOT_HEADER, SNAPSHOT_VERSION)?;
+
+        let mut currencies: BTreeSet<Currency> = BTreeSet::new();
+        let mut exchanges: BTreeSet<ExchangeType> = self.suspended.iter().cloned().collect();
+        currencies.extend(self.fees.withdrawal_fees.keys());
+        exchanges.extend(self.fees.trading_fees.keys());
+        for (source, destination) in self.fees.transfer_factors.keys() {
+            exchanges.extend(&[*source, *destination]);
+        }
+        for price in self.prices.iter() {
+            currencies.extend(&[*price.source_currency(), *price.destination_currency()]);
+            exchanges.insert(*price.exchange());
+        }
+        for ((source, destination), _) in self.expired.iter() {
+            currencies.extend(&[*source.currency(), *destination.currency()]);
+            exchanges.insert(*source.exchange());
+        }
+
+        for currency in currencies {
+            writeln!(writer, "CURRENCY {}", currency)?;
+        }
+        for exchange in exchanges {
+            writeln!(writer, "EXCHANGE {}", exchange)?;
+        }
+
+        let mut trading_fees: Vec<_> = self.fees.trading_fees.iter().collect();
+        trading_fees.sort();
+        for (exchange, fee) in trading_fees {
+            writeln!(writer, "TRADING_FEE {} {}", exchange, fee)?;
+        }
+
+        let mut withdrawal_fees: Vec<_> = self.fees.withdrawal_fees.iter().collect();
+        withdrawal_fees.sort();
+        for (currency, fee) in withdrawal_fees {
+            writeln!(writer, "WITHDRAWAL_FEE {} {}", currency, fee)?;
+        }
+
+        let mut transfer_factors: Vec<_> = self.fees.transfer_factors.iter().collect();
+        transfer_factors.sort();
+        for ((source, destination), factor) in transfer_factors {
+            writeln!(
+                writer,
+                "TRANSFER_FACTOR {} {} {}",
+                source, destination, factor
+            )?;
+        }
+
+        for exchange in self.suspended.iter() {
+            writeln!(writer, "SUSPENDED {}", exchange)?;
+        }
+
+        if let Some(max_age) = self.max_age {
+            writeln!(writer, "MAX_AGE {}", max_age.num_seconds())?;
+        }
+        if let Some(latest) = self.latest {
+            writeln!(writer, "LATEST {}", latest.format(TIMESTAMP_FORMAT))?;
+        }
+
+        // Expired markets keep the timestamp of their last price update, so
+        // older updates are still ignored once the snapshot is restored.
+        for ((source, destination), timestamp) in self.expired.iter() {
+            writeln!(
+                writer,
+                "EXPIRED {} {} {} {}",
+                timestamp.format(TIMESTAMP_FORMAT),
+                source.exchange(),
+                source.currency(),
+                destination.currency()
+            )?;
+        }
+
+        // The last trade and levels of an order book follow the price update
+        // they belong to.
+        for price in self.prices.iter() {
+            writeln!(writer, "PRICE {}", format_price_update(price))?;
+
+            if let Some(last) = price.last() {
+                writeln!(writer, "LAST {}", last)?;
+            }
+
+            if let Some(book) = price.order_book() {
+                for level in book.bids() {
+                    writeln!(writer, "BID {} {}", level.price(), level.size())?;
+                }
+                for level in book.asks() {
+                    writeln!(writer, "ASK {} {}", level.price(), level.size())?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read a snapshot written by `Snapshot::write`, rejecting any other version.
+    pub fn read(reader: impl BufRead) -> Result<Self, SnapshotError> {
+        let mut lines = reader.lines();
+
+        let header = lines.next().ok_or(SnapshotError::MissingHeader)??;
+        match header.split_whitespace().collect::<Vec<_>>().as_slice() {
+            [SNAPSHOT_HEADER, version] if *version == SNAPSHOT_VERSION.to_string() => {}
+            [SNAPSHOT_HEADER, version] => {
+                return Err(SnapshotError::UnsupportedVersion(version.to_string()));
+            }
+            _ => return Err(SnapshotError::MissingHeader),
+        }
+
+        let mut snapshot = Snapshot::default();
+        let mut books: Vec<(Vec<PriceLevel>, Vec<PriceLevel>)> = vec![];
+
+        for (index, line) in lines.enumerate() {
+            let line = line?;
+            let line_number = index + 2;
+
+            read_entry(&mut snapshot, &mut books, &line)
+                .map_err(|error| SnapshotError::InvalidEntry(line_number, error))?;
+        }
+
+        for (price, (bids, asks)) in snapshot.prices.iter_mut().zip(books) {
+            if !bids.is_empty() || !asks.is_empty() {
+                *price = price.clone().with_order_book(OrderBook::new(bids, asks));
+            }
+        }
+
+        Ok(snapshot)
+    }
+}
+
+/// Read a single entry of a snapshot into it, adding the levels of order
+/// books to the books of the price updates read so far.
+fn read_entry(
+    snapshot: &mut Snapshot,
+    books: &mut Vec<(Vec<PriceLevel>, Vec<PriceLevel>)>,
+    line: &str,
+) -> Result<(), LineParseError> {
+    match tokens(line).as_slice() {
+        [] => {}
+        [(_, "CURRENCY"), (_, symbol)] => {
+            Currency::register(symbol);
+        }
+        [(_, "EXCHANGE"), (_, symbol)] => {
+            ExchangeType::register(symbol);
+        }
+        [(_, "TRADING_FEE"), exchange_type, fee] => {
+            let exchange_type = exchange(*exchange_type)?;
+            snapshot
+                .fees
+                .trading_fees
+                .insert(exchange_type, decimal(*fee)?);
+        }
+        [(_, "WITHDRAWAL_FEE"), symbol, fee] => {
+            let symbol = currency(*symbol)?;
+            snapshot.fees.withdrawal_fees.insert(symbol, decimal(*fee)?);
+        }
+        [(_, "TRANSFER_FACTOR"), source, destination, factor] => {
+            let market = (exchange(*source)?, exchange(*destination)?);
+            snapshot
+                .fees
+                .transfer_factors
+                .insert(market, decimal(*factor)?);
+        }
+        [(_, "SUSPENDED"), exchange_type] => {
+            snapshot.suspended.push(exchange(*exchange_type)?);
+        }
+        [(_, "MAX_AGE"), max_age] => {
+            snapshot.max_age = Some(seconds(*max_age)?);
+        }
+        [(_, "LATEST"), latest] => {
+            snapshot.latest = Some(timestamp(*latest)?);
+        }
+        [(_, "EXPIRED"), expired_at, exchange_type, source, destination] => {
+            let expired_at = timestamp(*expired_at)?;
+            let exchange_type = exchange(*exchange_type)?;
+            let market = (
+                ExchangeVertex::new(exchange_type, currency(*source)?),
+                ExchangeVertex::new(exchange_type, currency(*destination)?),
+            );
+            snapshot.expired.push((market, expired_at));
+        }
+        [(_, "PRICE"), fields @ ..] => {
+            snapshot.prices.push(price_update(fields)?);
+            books.push((vec![], vec![]));
+        }
+        [(_, "LAST"), last] => {
+            let last = decimal(*last)?;
+            let price = snapshot
+                .prices
+                .last_mut()
+                .ok_or(LineParseError::MissingPriceUpdate)?;
+            *price = price.clone().with_last(last);
+        }
+        [(_, kind @ "BID"), price, size] | [(_, kind @ "ASK"), price, size] => {
+            let level = PriceLevel::new(decimal(*price)?, decimal(*size)?);
+            let (bids, asks) = books.last_mut().ok_or(LineParseError::MissingPriceUpdate)?;
+
+            let side = if *kind == "BID" { bids } else { asks };
+            side.push(level);
+        }
+        _ => return Err(LineParseError::UnknownLine),
+    }
+
+    Ok(())
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnapshotError::Io(_) => write!(f, "failed to read snapshot"),
+            SnapshotError::MissingHeader => write!(f, "missing snapshot header"),
+            SnapshotError::UnsupportedVersion(version) => {
+                write!(f, "unsupported snapshot version `{}`", version)
+            }
+            SnapshotError::InvalidEntry(line, error) => {
+                write!(f, "invalid snapshot entry on line {}: {}", line, error)
+            }
+        }
+    }
+}
+
+impl Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SnapshotError::Io(error) => Some(error),
+            SnapshotError::InvalidEntry(_, error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SnapshotError {
+    /// Convert from `io::Error` to `SnapshotError`.
+    fn from(error: io::Error) -> Self {
+        SnapshotError::Io(error)
+    }
+}
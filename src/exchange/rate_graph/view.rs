@@ -0,0 +1,252 @@
+use crate::{
+    exchange::{
+        rate_graph::routes::k_best_routes, ArbitrageCycle, BestRate, ExchangeVertex,
+        ExchangeVertexPair, FeeModel, Hop, Path, RateGraphError, RouteConstraints,
+    },
+    input::ExchangeRateRequest,
+};
+use chrono::{DateTime, Utc};
+use petgraph::graphmap::DiGraphMap;
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// The immutable part of a rate graph needed to answer exchange rate requests
+/// on its current rates: its edges, best paths, profitable cycles and markets.
+///
+/// Unlike a whole rate graph, a view carries no history, order book, or price
+/// update waiting to be applied, so it is cheap to publish after every change.
+#[derive(Debug, Clone)]
+pub struct RateView {
+    pub(crate) graph: DiGraphMap<ExchangeVertex, Decimal>,
+    pub(crate) path: Path<ExchangeVertex, Decimal>,
+    pub(crate) cycles: Vec<ArbitrageCycle>,
+    pub(crate) markets: HashMap<ExchangeVertexPair, DateTime<Utc>>,
+    pub(crate) expired: HashMap<ExchangeVertexPair, DateTime<Utc>>,
+    pub(crate) fees: FeeModel,
+}
+
+impl RateView {
+    /// Get the inner graph of the view.
+    pub fn graph(&self) -> &DiGraphMap<ExchangeVertex, Decimal> {
+        &self.graph
+    }
+
+    /// Get the profitable cycles found in the graph.
+    pub fn arbitrage_cycles(&self) -> &[ArbitrageCycle] {
+        &self.cycles
+    }
+
+    /// Same as `RateGraph::full_path`.
+    pub fn full_path(&self, request: &ExchangeRateRequest) -> Result<BestRate, RateGraphError> {
+        self.router().full_path(request)
+    }
+
+    /// Same as `RateGraph::full_path_with`.
+    pub fn full_path_with(
+        &self,
+        request: &ExchangeRateRequest,
+        constraints: &RouteConstraints,
+    ) -> Result<BestRate, RateGraphError> {
+        self.router().full_path_with(request, constraints)
+    }
+
+    /// Same as `RateGraph::k_best_paths`.
+    pub fn k_best_paths(
+        &self,
+        request: &ExchangeRateRequest,
+        k: usize,
+    ) -> Result<Vec<BestRate>, RateGraphError> {
+        self.router().k_best_paths(request, k)
+    }
+
+    fn router(&self) -> Router<'_> {
+        Router {
+            graph: &self.graph,
+            path: &self.path,
+            cycles: &self.cycles,
+            markets: &self.markets,
+            expired: &self.expired,
+            fees: &self.fees,
+        }
+    }
+}
+
+/// Everything needed to answer exchange rate requests, borrowed from either
+/// a rate graph or one of its views.
+pub(crate) struct Router<'a> {
+    pub graph: &'a DiGraphMap<ExchangeVertex, Decimal>,
+    pub path: &'a Path<ExchangeVertex, Decimal>,
+    pub cycles: &'a [ArbitrageCycle],
+    pub markets: &'a HashMap<ExchangeVertexPair, DateTime<Utc>>,
+    pub expired: &'a HashMap<ExchangeVertexPair, DateTime<Utc>>,
+    pub fees: &'a FeeModel,
+}
+
+impl<'a> Router<'a> {
+    /// Returns the most optimal sequence of trades and transfers across exchanges
+    /// for the specified exchange rate request.
+    pub fn full_path(&self, request: &ExchangeRateRequest) -> Result<BestRate, RateGraphError> {
+        let (u, v) = ExchangeVertexPair::from(request);
+
+        if let Some(cycle) = self.unbounded_cycle(u, v, &RouteConstraints::default()) {
+            return Err(RateGraphError::UnboundedRate(u, v, cycle.clone()));
+        }
+
+        let (full_path, _) = self
+            .path
+            .full_path(u, v)
+            .ok_or_else(|| self.unreachable(u, v))?;
+
+        Ok(self.best_rate(request, &full_path))
+    }
+
+    /// Returns the most optimal sequence of trades and transfers across exchanges
+    /// for the specified exchange rate request, which honors the given constraints.
+    pub fn full_path_with(
+        &self,
+        request: &ExchangeRateRequest,
+        constraints: &RouteConstraints,
+    ) -> Result<BestRate, RateGraphError> {
+        let (u, v) = ExchangeVertexPair::from(request);
+
+        if let Some(cycle) = self.unbounded_cycle(u, v, constraints) {
+            return Err(RateGraphError::UnboundedRate(u, v, cycle.clone()));
+        }
+
+        match constraints.best_route(self.graph, u, v) {
+            Some(route) => Ok(self.best_rate(request, &route)),
+            None if self.path.full_path(u, v).is_some() => {
+                Err(RateGraphError::UnsatisfiedConstraints(u, v))
+            }
+            None => Err(self.unreachable(u, v)),
+        }
+    }
+
+    /// Returns up to `k` loop-free sequences of trades and transfers for the
+    /// specified exchange rate request, ranked from the best rate.
+    pub fn k_best_paths(
+        &self,
+        request: &ExchangeRateRequest,
+        k: usize,
+    ) -> Result<Vec<BestRate>, RateGraphError> {
+        let (u, v) = ExchangeVertexPair::from(request);
+
+        if let Some(cycle) = self.unbounded_cycle(u, v, &RouteConstraints::default()) {
+            return Err(RateGraphError::UnboundedRate(u, v, cycle.clone()));
+        }
+
+        let routes = k_best_routes(self.graph, u, v, k);
+        if routes.is_empty() && k > 0 {
+            return Err(self.unreachable(u, v));
+        }
+
+        Ok(routes
+            .iter()
+            .map(|(route, _)| self.best_rate(request, route))
+            .collect())
+    }
+
+    /// Gets a profitable cycle which can be reached from `u`, and reaches `v`,
+    /// through the vertices the constraints allow.
+    ///
+    /// Cycles going through a vertex the constraints don't allow, or which
+    /// can't be repeated within the maximum number of hops or transfers, are ignored.
+    fn unbounded_cycle(
+        &self,
+        u: ExchangeVertex,
+        v: ExchangeVertex,
+        constraints: &RouteConstraints,
+    ) -> Option<&'a ArbitrageCycle> {
+        if constraints.max_hops().is_some() || self.cycles.is_empty() {
+            return None;
+        }
+
+        let from_u = constraints.transfers_from(self.graph, u);
+        self.cycles.iter().find(|cycle| {
+            let vertices = cycle.vertices();
+            let transfers = vertices
+                .iter()
+                .zip(vertices.iter().cycle().skip(1))
+                .filter(|(x, y)| x.exchange() != y.exchange())
+                .count();
+
+            if transfers > 0 && constraints.max_transfers().is_some() {
+                return false;
+            }
+
+            let into = from_u.get(&vertices[0]);
+            let out_of = constraints
+                .transfers_from(self.graph, vertices[0])
+                .get(&v)
+                .cloned();
+
+            match (into, out_of) {
+                (Some(into), Some(out_of)) => {
+                    vertices.iter().all(|vertex| constraints.allows(vertex))
+                        && constraints
+                            .max_transfers()
+                            .is_none_or(|max| into + out_of <= max)
+                }
+                _ => false,
+            }
+        })
+    }
+
+    /// Gets the error of a request between two vertices without any path.
+    ///
+    /// The rate is only stale when `v` can't be reached from `u` through the
+    /// live markets, but could be if the expired markets were still part of
+    /// the graph.
+    fn unreachable(&self, u: ExchangeVertex, v: ExchangeVertex) -> RateGraphError {
+        if !self.connected(u, v, false) && self.connected(u, v, true) {
+            RateGraphError::StaleRate(u, v)
+        } else {
+            RateGraphError::NoEdgesBetweenNodes(u, v)
+        }
+    }
+
+    /// Checks if a path between two vertices exists through the live markets,
+    /// along with the expired ones if `expired` is set.
+    fn connected(&self, u: ExchangeVertex, v: ExchangeVertex, expired: bool) -> bool {
+        let mut visited = HashSet::new();
+        let mut queue = VecDeque::from(vec![u]);
+
+        while let Some(node) = queue.pop_front() {
+            if node == v {
+                return true;
+            }
+
+            if !visited.insert(node) {
+                continue;
+            }
+
+            let stale = self
+                .expired
+                .keys()
+                .filter(|(src, _)| expired && *src == node);
+            queue.extend(self.graph.neighbors(node).chain(stale.map(|(_, dst)| *dst)));
+        }
+
+        false
+    }
+
+    /// Build the best rate of a request going through a given sequence of vertices.
+    fn best_rate(&self, request: &ExchangeRateRequest, route: &[ExchangeVertex]) -> BestRate {
+        let hops = route
+            .windows(2)
+            .map(|pair| {
+                let (src, dst) = (pair[0], pair[1]);
+                let rate = *self.graph.edge_weight(src, dst).unwrap();
+                let fee = if src.exchange() == dst.exchange() {
+                    0.into()
+                } else {
+                    self.fees.withdrawal_fee(*src.currency())
+                };
+
+                Hop::new(src, dst, rate, fee, self.markets.get(&(src, dst)).cloned())
+            })
+            .collect();
+
+        BestRate::new(request.clone(), hops)
+    }
+}
@@ -1,20 +1,42 @@
+use crate::exchange::resources::registry::CURRENCIES;
 use std::{
-    fmt::{self, Display},
+    error::Error,
+    fmt::{self, Debug, Display},
     str::FromStr,
 };
 
-/// An enum of all supported currency values for use in an exchange.
-#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Eq, Hash, Ord)]
-pub enum Currency {
-    USD,
-    BTC,
-    LTC,
-}
+/// A currency available for use in an exchange, interned from its ticker symbol.
+///
+/// Currencies other than the built-in ones can be registered at runtime.
+#[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Hash, Ord)]
+pub struct Currency(u32);
 
 /// Errors that occur while parsing a given string value into a valid Currency.
 #[derive(Debug)]
 pub enum CurrencyParseError {
-    UnsupportedCurrency,
+    /// The symbol isn't registered, and discovery is disabled or exhausted.
+    UnsupportedCurrency(String),
+}
+
+impl Currency {
+    pub const USD: Currency = Currency(0);
+    pub const BTC: Currency = Currency(1);
+    pub const LTC: Currency = Currency(2);
+
+    /// Register a currency ticker symbol, or get it if it already exists.
+    pub fn register(symbol: &str) -> Currency {
+        Currency(CURRENCIES.write().unwrap().intern(symbol))
+    }
+
+    /// Get an already registered currency from its ticker symbol.
+    pub fn lookup(symbol: &str) -> Option<Currency> {
+        CURRENCIES.read().unwrap().id(symbol).map(Currency)
+    }
+
+    /// Gets the ticker symbol of a currency.
+    pub fn symbol(&self) -> &'static str {
+        CURRENCIES.read().unwrap().symbol(self.0).unwrap_or("?")
+    }
 }
 
 impl FromStr for Currency {
@@ -22,23 +44,58 @@ impl FromStr for Currency {
 
     /// Get an equivalent Currency type from a given string slice.
     fn from_str(s: &str) -> Result<Currency, Self::Err> {
-        match s.to_lowercase().as_ref() {
-            "usd" => Ok(Currency::USD),
-            "btc" => Ok(Currency::BTC),
-            "ltc" => Ok(Currency::LTC),
-            _ => Err(CurrencyParseError::UnsupportedCurrency),
+        if let Some(found) = Self::lookup(s) {
+            return Ok(found);
         }
+
+        CURRENCIES
+            .write()
+            .unwrap()
+            .resolve(s)
+            .map(Currency)
+            .ok_or_else(|| CurrencyParseError::UnsupportedCurrency(s.to_string()))
     }
 }
 
 impl Display for Currency {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let currency = match self {
-            Currency::BTC => "BTC",
-            Currency::LTC => "LTC",
-            Currency::USD => "USD",
-        };
+        write!(f, "{}", self.symbol())
+    }
+}
+
+impl Debug for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+impl Display for CurrencyParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CurrencyParseError::UnsupportedCurrency(symbol) => {
+                write!(f, "unsupported currency `{}`", symbol)
+            }
+        }
+    }
+}
+
+impl Error for CurrencyParseError {}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Currency {
+    /// Serialize a currency as its symbol.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.symbol())
+    }
+}
 
-        write!(f, "{}", currency)
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Currency {
+    /// Deserialize a currency from its symbol, as it would be parsed from a string slice.
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let symbol = String::deserialize(deserializer)?;
+        symbol
+            .parse()
+            .map_err(|error: CurrencyParseError| serde::de::Error::custom(error))
     }
 }
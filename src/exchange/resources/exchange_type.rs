@@ -1,19 +1,41 @@
+use crate::exchange::resources::registry::EXCHANGES;
 use std::{
-    fmt::{self, Display},
+    error::Error,
+    fmt::{self, Debug, Display},
     str::FromStr,
 };
 
-/// An enum of all suporeted exchange type available for use in an exchange.
-#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Eq, Hash, Ord)]
-pub enum ExchangeType {
-    GDAX,
-    KRAKEN,
-}
+/// An exchange (i.e. trading venue), interned from its venue symbol.
+///
+/// Exchanges other than the built-in ones can be registered at runtime.
+#[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Hash, Ord)]
+pub struct ExchangeType(u32);
 
 /// Errors that occur while parsing a given string value into a valid EchangeType.
 #[derive(Debug)]
 pub enum ExchangeTypeParseError {
-    UnsupportedExchange,
+    /// The symbol isn't registered, and discovery is disabled or exhausted.
+    UnsupportedExchange(String),
+}
+
+impl ExchangeType {
+    pub const GDAX: ExchangeType = ExchangeType(0);
+    pub const KRAKEN: ExchangeType = ExchangeType(1);
+
+    /// Register an exchange venue symbol, or get it if it already exists.
+    pub fn register(symbol: &str) -> ExchangeType {
+        ExchangeType(EXCHANGES.write().unwrap().intern(symbol))
+    }
+
+    /// Get an already registered exchange from its venue symbol.
+    pub fn lookup(symbol: &str) -> Option<ExchangeType> {
+        EXCHANGES.read().unwrap().id(symbol).map(ExchangeType)
+    }
+
+    /// Gets the venue symbol of an exchange.
+    pub fn symbol(&self) -> &'static str {
+        EXCHANGES.read().unwrap().symbol(self.0).unwrap_or("?")
+    }
 }
 
 impl FromStr for ExchangeType {
@@ -21,21 +43,58 @@ impl FromStr for ExchangeType {
 
     /// Get an equivalent ExchangeType type from a given string slice.
     fn from_str(s: &str) -> Result<ExchangeType, Self::Err> {
-        match s.to_lowercase().as_ref() {
-            "gdax" => Ok(ExchangeType::GDAX),
-            "kraken" => Ok(ExchangeType::KRAKEN),
-            _ => Err(ExchangeTypeParseError::UnsupportedExchange),
+        if let Some(found) = Self::lookup(s) {
+            return Ok(found);
         }
+
+        EXCHANGES
+            .write()
+            .unwrap()
+            .resolve(s)
+            .map(ExchangeType)
+            .ok_or_else(|| ExchangeTypeParseError::UnsupportedExchange(s.to_string()))
     }
 }
 
 impl Display for ExchangeType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let currency = match self {
-            ExchangeType::GDAX => "GDAX",
-            ExchangeType::KRAKEN => "KRAKEN",
-        };
+        write!(f, "{}", self.symbol())
+    }
+}
+
+impl Debug for ExchangeType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+impl Display for ExchangeTypeParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExchangeTypeParseError::UnsupportedExchange(symbol) => {
+                write!(f, "unsupported exchange `{}`", symbol)
+            }
+        }
+    }
+}
+
+impl Error for ExchangeTypeParseError {}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ExchangeType {
+    /// Serialize a exchange as its symbol.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.symbol())
+    }
+}
 
-        write!(f, "{}", currency)
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ExchangeType {
+    /// Deserialize a exchange from its symbol, as it would be parsed from a string slice.
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let symbol = String::deserialize(deserializer)?;
+        symbol
+            .parse()
+            .map_err(|error: ExchangeTypeParseError| serde::de::Error::custom(error))
     }
 }
@@ -1,4 +1,11 @@
 mod currency;
 mod exchange_type;
+mod registry;
 
-pub use self::{currency::*, exchange_type::*};
+pub use self::{
+    currency::*,
+    exchange_type::*,
+    registry::{
+        discover_symbols, load_registry, Registry, RegistryLoadError, MAX_DISCOVERED_SYMBOLS,
+    },
+};
@@ -0,0 +1,169 @@
+use crate::exchange::resources::{Currency, ExchangeType};
+use lazy_static::lazy_static;
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Display},
+    io::{self, BufRead},
+    sync::RwLock,
+};
+
+lazy_static! {
+    /// Registry of every currency ticker known to the running process.
+    pub(crate) static ref CURRENCIES: RwLock<Registry> =
+        RwLock::new(Registry::new(&["USD", "BTC", "LTC"]));
+
+    /// Registry of every exchange venue known to the running process.
+    pub(crate) static ref EXCHANGES: RwLock<Registry> =
+        RwLock::new(Registry::new(&["GDAX", "KRAKEN"]));
+}
+
+/// Maximum number of symbols a registry discovers while parsing, so that
+/// untrusted input can't grow it, and leak its symbols, without bound.
+pub const MAX_DISCOVERED_SYMBOLS: usize = 1024;
+
+/// Interns symbols (e.g. tickers or venues) into small ids, so they can be
+/// copied and hashed cheaply, and maps those ids back to their symbols.
+///
+/// Symbols are case insensitive, and always stored in uppercase.
+#[derive(Debug, Default)]
+pub struct Registry {
+    symbols: Vec<&'static str>,
+    ids: HashMap<&'static str, u32>,
+    discover: bool,
+    discovered: usize,
+}
+
+/// Errors that occur while loading a registry configuration.
+#[derive(Debug)]
+pub enum RegistryLoadError {
+    Io(io::Error),
+    InvalidEntry(usize),
+}
+
+impl Registry {
+    /// Create a new registry, pre-filled with the specified symbols.
+    pub fn new(symbols: &[&str]) -> Self {
+        let mut registry = Self::default();
+        for symbol in symbols {
+            registry.intern(symbol);
+        }
+
+        registry
+    }
+
+    /// Gets the id of a symbol, registering the symbol if it is unknown.
+    ///
+    /// Interned symbols live for the rest of the process.
+    pub fn intern(&mut self, symbol: &str) -> u32 {
+        let symbol = symbol.to_uppercase();
+        if let Some(id) = self.ids.get(symbol.as_str()) {
+            return *id;
+        }
+
+        let symbol: &'static str = Box::leak(symbol.into_boxed_str());
+        let id = self.symbols.len() as u32;
+        self.symbols.push(symbol);
+        self.ids.insert(symbol, id);
+        id
+    }
+
+    /// Gets the id of a symbol. Unknown symbols are only registered when
+    /// discovery is enabled, and fewer than `MAX_DISCOVERED_SYMBOLS` have
+    /// been discovered so far.
+    pub fn resolve(&mut self, symbol: &str) -> Option<u32> {
+        match self.id(symbol) {
+            None if self.discover && self.discovered < MAX_DISCOVERED_SYMBOLS => {
+                self.discovered += 1;
+                Some(self.intern(symbol))
+            }
+            id => id,
+        }
+    }
+
+    /// Gets the id of an already registered symbol.
+    pub fn id(&self, symbol: &str) -> Option<u32> {
+        self.ids.get(symbol.to_uppercase().as_str()).cloned()
+    }
+
+    /// Gets the symbol registered for a given id.
+    pub fn symbol(&self, id: u32) -> Option<&'static str> {
+        self.symbols.get(id as usize).cloned()
+    }
+
+    /// Enable or disable the registration of unknown symbols while parsing.
+    pub fn set_discover(&mut self, discover: bool) {
+        self.discover = discover;
+    }
+}
+
+/// Enable or disable the registration of unknown currencies and exchanges,
+/// as they are discovered while parsing price updates and exchange rate requests.
+pub fn discover_symbols(discover: bool) {
+    CURRENCIES.write().unwrap().set_discover(discover);
+    EXCHANGES.write().unwrap().set_discover(discover);
+}
+
+/// Register the currencies and exchanges listed in a given configuration.
+///
+/// Every non-empty line that isn't a `#` comment must follow the format:
+/// `CURRENCY <symbol>` or `EXCHANGE <symbol>`.
+///
+/// For example:
+///
+/// ```
+/// use exchange_rate::exchange::{load_registry, Currency, ExchangeType};
+///
+/// let config = "CURRENCY ETH\nEXCHANGE BINANCE\n";
+/// load_registry(config.as_bytes()).unwrap();
+///
+/// assert_eq!(Currency::lookup("eth").unwrap().symbol(), "ETH");
+/// assert_eq!(ExchangeType::lookup("binance").unwrap().symbol(), "BINANCE");
+/// ```
+pub fn load_registry(reader: impl BufRead) -> Result<(), RegistryLoadError> {
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        let mut values = line.split_whitespace();
+
+        match (values.next(), values.next(), values.next()) {
+            (None, _, _) => {}
+            (Some(comment), _, _) if comment.starts_with('#') => {}
+            (Some(kind), Some(symbol), None) if kind.eq_ignore_ascii_case("currency") => {
+                Currency::register(symbol);
+            }
+            (Some(kind), Some(symbol), None) if kind.eq_ignore_ascii_case("exchange") => {
+                ExchangeType::register(symbol);
+            }
+            _ => return Err(RegistryLoadError::InvalidEntry(index + 1)),
+        }
+    }
+
+    Ok(())
+}
+
+impl Display for RegistryLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RegistryLoadError::Io(_) => write!(f, "failed to read registry"),
+            RegistryLoadError::InvalidEntry(line) => {
+                write!(f, "invalid registry entry on line {}", line)
+            }
+        }
+    }
+}
+
+impl Error for RegistryLoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RegistryLoadError::Io(error) => Some(error),
+            RegistryLoadError::InvalidEntry(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for RegistryLoadError {
+    /// Convert from `io::Error` to `RegistryLoadError`.
+    fn from(error: io::Error) -> Self {
+        RegistryLoadError::Io(error)
+    }
+}
@@ -0,0 +1,363 @@
+use crate::{exchange::InsertReport, input::PriceUpdate, utils::time_utils::parse_timestamp};
+use rust_decimal::Decimal;
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    io::{self, BufRead},
+    str::FromStr,
+};
+
+/// Maps the named columns of a CSV export to the fields of a price update.
+///
+/// The default mapping reads exports with the header `time,venue,base,quote,bid,ask`.
+/// Column names are matched regardless of case and surrounding whitespace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvMapping {
+    timestamp: String,
+    exchange: String,
+    base: String,
+    quote: String,
+    rates: RateColumns,
+    delimiter: char,
+}
+
+/// The columns holding the rates of a market.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RateColumns {
+    /// The best bid and ask prices of the base currency, in the quote currency.
+    BidAsk { bid: String, ask: String },
+
+    /// The forward and backward factors, as in the format parsed by
+    /// `PriceUpdate::from_str`.
+    Factors { forward: String, backward: String },
+}
+
+/// Errors that can occur while reading price updates from a CSV export.
+///
+/// Row numbers are the line numbers the rows start on in the export, counting
+/// the header as the first line, even when quoted fields span several lines.
+#[derive(Debug)]
+pub enum CsvError {
+    Io(io::Error),
+    MissingHeader,
+    MissingColumn(String),
+    IncompleteRow(usize),
+    InvalidField {
+        row: usize,
+        column: String,
+        value: String,
+    },
+}
+
+/// The outcome of inserting the rows of a CSV export into a rate graph.
+#[derive(Debug, Default)]
+pub struct CsvReport {
+    inserted: InsertReport,
+    errors: Vec<CsvError>,
+}
+
+/// Reads the rows of a CSV export as price updates, one row at a time.
+pub(crate) struct CsvReader<R: BufRead> {
+    reader: R,
+    columns: Columns,
+    delimiter: char,
+    row: usize,
+    lines: usize,
+}
+
+/// Positions of the mapped columns in a CSV export, ordered as the fields of
+/// a price update.
+struct Columns {
+    names: [String; 6],
+    indices: [usize; 6],
+    bid_ask: bool,
+}
+
+impl CsvMapping {
+    /// Set the column holding the timestamp of every update.
+    pub fn with_timestamp_column(mut self, column: &str) -> Self {
+        self.timestamp = column.to_string();
+        self
+    }
+
+    /// Set the column holding the exchange of every update.
+    pub fn with_exchange_column(mut self, column: &str) -> Self {
+        self.exchange = column.to_string();
+        self
+    }
+
+    /// Set the column holding the base (i.e. source) currency of every update.
+    pub fn with_base_column(mut self, column: &str) -> Self {
+        self.base = column.to_string();
+        self
+    }
+
+    /// Set the column holding the quote (i.e. destination) currency of every update.
+    pub fn with_quote_column(mut self, column: &str) -> Self {
+        self.quote = column.to_string();
+        self
+    }
+
+    /// Read the rates of every update from its best bid and ask prices.
+    pub fn with_bid_ask_columns(mut self, bid: &str, ask: &str) -> Self {
+        self.rates = RateColumns::BidAsk {
+            bid: bid.to_string(),
+            ask: ask.to_string(),
+        };
+        self
+    }
+
+    /// Read the rates of every update from its forward and backward factors.
+    pub fn with_factor_columns(mut self, forward: &str, backward: &str) -> Self {
+        self.rates = RateColumns::Factors {
+            forward: forward.to_string(),
+            backward: backward.to_string(),
+        };
+        self
+    }
+
+    /// Set the character separating the fields of a row, `,` by default.
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+}
+
+impl Default for CsvMapping {
+    // Create a mapping for exports with the header `time,venue,base,quote,bid,ask`.
+    fn default() -> Self {
+        Self {
+            timestamp: "time".to_string(),
+            exchange: "venue".to_string(),
+            base: "base".to_string(),
+            quote: "quote".to_string(),
+            rates: RateColumns::BidAsk {
+                bid: "bid".to_string(),
+                ask: "ask".to_string(),
+            },
+            delimiter: ',',
+        }
+    }
+}
+
+impl CsvReport {
+    /// Gets the report of inserting the updates of every valid row.
+    pub fn inserted(&self) -> &InsertReport {
+        &self.inserted
+    }
+
+    /// Gets the errors of the rows which couldn't be read, in row order.
+    pub fn errors(&self) -> &[CsvError] {
+        &self.errors
+    }
+
+    pub(crate) fn insert(&mut self, report: InsertReport) {
+        self.inserted.extend(report);
+    }
+
+    pub(crate) fn fail(&mut self, error: CsvError) {
+        self.errors.push(error);
+    }
+}
+
+impl<R: BufRead> CsvReader<R> {
+    /// Read the header of a CSV export, and find the mapped columns in it.
+    pub fn new(mut reader: R, mapping: &CsvMapping) -> Result<Self, CsvError> {
+        let (header, lines) = read_record(&mut reader)?.ok_or(CsvError::MissingHeader)?;
+        let header = split_record(&header, mapping.delimiter);
+
+        let (rate1, rate2, bid_ask) = match &mapping.rates {
+            RateColumns::BidAsk { bid, ask } => (bid, ask, true),
+            RateColumns::Factors { forward, backward } => (forward, backward, false),
+        };
+        let names = [
+            mapping.timestamp.clone(),
+            mapping.exchange.clone(),
+            mapping.base.clone(),
+            mapping.quote.clone(),
+            rate1.clone(),
+            rate2.clone(),
+        ];
+
+        let mut indices = [0; 6];
+        for (index, name) in indices.iter_mut().zip(names.iter()) {
+            *index = header
+                .iter()
+                .position(|column| column.trim().eq_ignore_ascii_case(name.trim()))
+                .ok_or_else(|| CsvError::MissingColumn(name.clone()))?;
+        }
+
+        Ok(Self {
+            reader,
+            columns: Columns {
+                names,
+                indices,
+                bid_ask,
+            },
+            delimiter: mapping.delimiter,
+            row: 1,
+            lines,
+        })
+    }
+
+    /// Parse a single row into a price update.
+    fn parse_row(&self, line: &str) -> Result<PriceUpdate, CsvError> {
+        let fields = split_record(line, self.delimiter);
+        let columns = &self.columns;
+
+        if columns.indices.iter().any(|index| *index >= fields.len()) {
+            return Err(CsvError::IncompleteRow(self.row));
+        }
+
+        let invalid = |field: usize| CsvError::InvalidField {
+            row: self.row,
+            column: columns.names[field].clone(),
+            value: fields[columns.indices[field]].trim().to_string(),
+        };
+        let value = |field: usize| fields[columns.indices[field]].trim();
+        let rate = |field: usize| {
+            Decimal::from_str(value(field))
+                .ok()
+                .filter(|rate| *rate > Decimal::from(0))
+                .ok_or_else(|| invalid(field))
+        };
+
+        let timestamp = parse_timestamp(value(0)).map_err(|_| invalid(0))?;
+        let exchange = value(1).parse().map_err(|_| invalid(1))?;
+        let base = value(2).parse().map_err(|_| invalid(2))?;
+        let quote = value(3).parse().map_err(|_| invalid(3))?;
+
+        if columns.bid_ask {
+            Ok(PriceUpdate::from_bid_ask(
+                timestamp,
+                exchange,
+                base,
+                quote,
+                rate(4)?,
+                rate(5)?,
+            ))
+        } else {
+            Ok(PriceUpdate::new(
+                timestamp,
+                exchange,
+                base,
+                quote,
+                rate(4)?,
+                rate(5)?,
+            ))
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for CsvReader<R> {
+    type Item = Result<PriceUpdate, CsvError>;
+
+    /// Read the next row of the export, skipping empty ones.
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (record, lines) = match read_record(&mut self.reader) {
+                Ok(record) => record?,
+                Err(error) => return Some(Err(CsvError::Io(error))),
+            };
+            self.row = self.lines + 1;
+            self.lines += lines;
+
+            if !record.trim().is_empty() {
+                return Some(self.parse_row(&record));
+            }
+        }
+    }
+}
+
+/// Read a single record, without its final line break, along with the number
+/// of lines it spans: a quoted field can hold line breaks, so a record goes on
+/// until its quotes are balanced. Returns `None` at the end of the export.
+fn read_record(reader: &mut impl BufRead) -> io::Result<Option<(String, usize)>> {
+    let mut record = String::new();
+    let mut lines = 0;
+
+    while reader.read_line(&mut record)? > 0 {
+        lines += 1;
+
+        // Escaped quotes come in pairs, so they never unbalance a record.
+        if record.matches('"').count().is_multiple_of(2) {
+            break;
+        }
+    }
+
+    if lines == 0 {
+        return Ok(None);
+    }
+
+    if record.ends_with('\n') {
+        record.pop();
+    }
+    Ok(Some((record, lines)))
+}
+
+/// Split a single record into its fields, removing the quotes around them.
+/// Quoted fields can hold the delimiter, line breaks, and quotes escaped as `""`.
+fn split_record(line: &str, delimiter: char) -> Vec<String> {
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == delimiter && !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+
+    fields.push(field);
+    fields
+}
+
+impl CsvError {
+    /// Gets the row of the error, unless it is about the whole export.
+    pub fn row(&self) -> Option<usize> {
+        match self {
+            CsvError::IncompleteRow(row) | CsvError::InvalidField { row, .. } => Some(*row),
+            _ => None,
+        }
+    }
+}
+
+impl Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CsvError::Io(_) => write!(f, "failed to read CSV export"),
+            CsvError::MissingHeader => write!(f, "missing CSV header"),
+            CsvError::MissingColumn(column) => write!(f, "missing CSV column `{}`", column),
+            CsvError::IncompleteRow(row) => write!(f, "incomplete row {}", row),
+            CsvError::InvalidField { row, column, value } => write!(
+                f,
+                "invalid value `{}` in column `{}` of row {}",
+                value, column, row
+            ),
+        }
+    }
+}
+
+impl Error for CsvError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CsvError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for CsvError {
+    /// Convert from `io::Error` to `CsvError`.
+    fn from(error: io::Error) -> Self {
+        CsvError::Io(error)
+    }
+}
@@ -1,12 +1,20 @@
-use crate::exchange::{
-    Currency, CurrencyParseError, ExchangeType, ExchangeTypeParseError, ExchangeVertex,
-    ExchangeVertexPair,
+use crate::{
+    exchange::{
+        Currency, CurrencyParseError, ExchangeType, ExchangeTypeParseError, ExchangeVertex,
+        ExchangeVertexPair,
+    },
+    utils::str_utils::tokens,
+};
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    str::FromStr,
 };
-use std::str::FromStr;
 
 /// Represents a request to convert from given currency on
 /// a source exchange to another currency on a destionation exchange.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ExchangeRateRequest {
     source_exchange: ExchangeType,
     source_currency: Currency,
@@ -16,11 +24,22 @@ pub struct ExchangeRateRequest {
 
 /// Error that occur while parsing into ExchangeRateRequest
 /// from a given string slice(`&str`).
+///
+/// Errors about a single field carry the offending token, and the column
+/// (counted in characters, from one) where it starts.
 #[derive(Debug)]
 pub enum ExchangeRateRequestParseError {
     IncompleteData,
-    InvalidCurrency(CurrencyParseError),
-    InvalidExchange(ExchangeTypeParseError),
+    InvalidCurrency {
+        token: String,
+        column: usize,
+        source: CurrencyParseError,
+    },
+    InvalidExchange {
+        token: String,
+        column: usize,
+        source: ExchangeTypeParseError,
+    },
 }
 
 impl ExchangeRateRequest {
@@ -54,17 +73,60 @@ impl ExchangeRateRequest {
     }
 }
 
-impl From<CurrencyParseError> for ExchangeRateRequestParseError {
-    /// Convert from `CurrencyParseError` to `ExchangeRateRequestParseError`.
-    fn from(error: CurrencyParseError) -> Self {
-        ExchangeRateRequestParseError::InvalidCurrency(error)
+impl ExchangeRateRequestParseError {
+    /// Gets the offending token, unless the request is incomplete.
+    pub fn token(&self) -> Option<&str> {
+        use ExchangeRateRequestParseError::*;
+
+        match self {
+            IncompleteData => None,
+            InvalidCurrency { token, .. } | InvalidExchange { token, .. } => Some(token),
+        }
+    }
+
+    /// Gets the column where the offending token starts, unless the request
+    /// is incomplete.
+    pub fn column(&self) -> Option<usize> {
+        use ExchangeRateRequestParseError::*;
+
+        match self {
+            IncompleteData => None,
+            InvalidCurrency { column, .. } | InvalidExchange { column, .. } => Some(*column),
+        }
     }
 }
 
-impl From<ExchangeTypeParseError> for ExchangeRateRequestParseError {
-    /// Convert from `ExchangeTypeParseError` to `ExchangeRateRequestParseError`.
-    fn from(error: ExchangeTypeParseError) -> Self {
-        ExchangeRateRequestParseError::InvalidExchange(error)
+impl Display for ExchangeRateRequestParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ExchangeRateRequestParseError::*;
+
+        let kind = match self {
+            IncompleteData => {
+                return write!(f, "incomplete exchange rate request, expected 5 fields")
+            }
+            InvalidCurrency { .. } => "currency",
+            InvalidExchange { .. } => "exchange",
+        };
+
+        write!(
+            f,
+            "invalid {} `{}` at column {}",
+            kind,
+            self.token().unwrap(),
+            self.column().unwrap()
+        )
+    }
+}
+
+impl Error for ExchangeRateRequestParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use ExchangeRateRequestParseError::*;
+
+        match self {
+            IncompleteData => None,
+            InvalidCurrency { source, .. } => Some(source),
+            InvalidExchange { source, .. } => Some(source),
+        }
     }
 }
 
@@ -104,19 +166,36 @@ impl FromStr for ExchangeRateRequest {
     ///
     ///
     fn from_str(data: &str) -> Result<Self, Self::Err> {
-        let mut values = data.split_whitespace();
+        use ExchangeRateRequestParseError::*;
 
-        if values.clone().count() != 5 {
-            return Err(ExchangeRateRequestParseError::IncompleteData);
+        // The first token is `EXCHANGE_RATE_REQUEST`.
+        let values = tokens(data);
+        if values.len() != 5 {
+            return Err(IncompleteData);
         }
 
-        values.next(); // Skips `EXCHANGE_RATE_REQUEST`
+        let exchange = |index: usize| {
+            let (column, token) = values[index];
+            token.parse().map_err(|source| InvalidExchange {
+                token: token.to_string(),
+                column,
+                source,
+            })
+        };
+        let currency = |index: usize| {
+            let (column, token) = values[index];
+            token.parse().map_err(|source| InvalidCurrency {
+                token: token.to_string(),
+                column,
+                source,
+            })
+        };
 
         Ok(Self {
-            source_exchange: values.next().unwrap().parse()?,
-            source_currency: values.next().unwrap().parse()?,
-            destination_exchange: values.next().unwrap().parse()?,
-            destination_currency: values.next().unwrap().parse()?,
+            source_exchange: exchange(1)?,
+            source_currency: currency(2)?,
+            destination_exchange: exchange(3)?,
+            destination_currency: currency(4)?,
         })
     }
 }
@@ -0,0 +1,236 @@
+use crate::{
+    exchange::{Currency, CurrencyParseError, ExchangeType, ExchangeTypeParseError},
+    input::{ExchangeRateRequestParseError, PriceUpdate, PriceUpdateParseError},
+    utils::time_utils::{parse_timestamp, TIMESTAMP_FORMAT},
+};
+use chrono::{DateTime, Duration, ParseError, Utc};
+use rust_decimal::Decimal;
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    num::ParseIntError,
+    str::FromStr,
+};
+
+/// Errors that can occur while parsing a single line of a snapshot, a price
+/// log or a backtest file.
+///
+/// Errors about a single field carry the offending token, and the column
+/// (counted in characters, from one) where it starts.
+#[derive(Debug)]
+pub enum LineParseError {
+    /// The line isn't of any known kind, or has the wrong number of fields.
+    UnknownLine,
+
+    /// The line belongs to a price update, but follows none.
+    MissingPriceUpdate,
+
+    InvalidPriceUpdate(PriceUpdateParseError),
+    InvalidRequest(ExchangeRateRequestParseError),
+    InvalidNumber {
+        token: String,
+        column: usize,
+        source: rust_decimal::Error,
+    },
+    InvalidDuration {
+        token: String,
+        column: usize,
+        source: ParseIntError,
+    },
+    TimestampError {
+        token: String,
+        column: usize,
+        source: ParseError,
+    },
+    InvalidCurrency {
+        token: String,
+        column: usize,
+        source: CurrencyParseError,
+    },
+    InvalidExchangeType {
+        token: String,
+        column: usize,
+        source: ExchangeTypeParseError,
+    },
+}
+
+/// Parse a decimal field, along with the column it starts at.
+pub(crate) fn decimal((column, token): (usize, &str)) -> Result<Decimal, LineParseError> {
+    Decimal::from_str(token).map_err(|source| LineParseError::InvalidNumber {
+        token: token.to_string(),
+        column,
+        source,
+    })
+}
+
+/// Parse a duration field, in seconds, along with the column it starts at.
+pub(crate) fn seconds((column, token): (usize, &str)) -> Result<Duration, LineParseError> {
+    token
+        .parse()
+        .map(Duration::seconds)
+        .map_err(|source| LineParseError::InvalidDuration {
+            token: token.to_string(),
+            column,
+            source,
+        })
+}
+
+/// Parse a timestamp field, along with the column it starts at.
+pub(crate) fn timestamp((column, token): (usize, &str)) -> Result<DateTime<Utc>, LineParseError> {
+    parse_timestamp(token).map_err(|source| LineParseError::TimestampError {
+        token: token.to_string(),
+        column,
+        source,
+    })
+}
+
+/// Parse a currency field, along with the column it starts at.
+pub(crate) fn currency((column, token): (usize, &str)) -> Result<Currency, LineParseError> {
+    token
+        .parse()
+        .map_err(|source| LineParseError::InvalidCurrency {
+            token: token.to_string(),
+            column,
+            source,
+        })
+}
+
+/// Parse an exchange field, along with the column it starts at.
+pub(crate) fn exchange((column, token): (usize, &str)) -> Result<ExchangeType, LineParseError> {
+    token
+        .parse()
+        .map_err(|source| LineParseError::InvalidExchangeType {
+            token: token.to_string(),
+            column,
+            source,
+        })
+}
+
+/// Format the rates of a price update, without its last trade and order book:
+/// `<timestamp> <exchange> <source> <destination> <bid> <ask> <backward_factor>`.
+///
+/// Both the ask and the backward factor are written, as either one can be
+/// rounded when derived from the other.
+pub(crate) fn format_price_update(update: &PriceUpdate) -> String {
+    format!(
+        "{} {} {} {} {} {} {}",
+        update.timestamp().format(TIMESTAMP_FORMAT),
+        update.exchange(),
+        update.source_currency(),
+        update.destination_currency(),
+        update.bid(),
+        update.ask(),
+        update.backward_factor()
+    )
+}
+
+/// Parse the fields of a price update, as written by `format_price_update`.
+pub(crate) fn price_update(fields: &[(usize, &str)]) -> Result<PriceUpdate, LineParseError> {
+    match fields {
+        [at, exchange_type, source, destination, bid, ask, backward] => {
+            Ok(PriceUpdate::from_parts(
+                timestamp(*at)?,
+                exchange(*exchange_type)?,
+                currency(*source)?,
+                currency(*destination)?,
+                decimal(*bid)?,
+                decimal(*ask)?,
+                decimal(*backward)?,
+            ))
+        }
+        _ => Err(LineParseError::UnknownLine),
+    }
+}
+
+impl LineParseError {
+    /// Gets the offending token, if the error is about a single field.
+    pub fn token(&self) -> Option<&str> {
+        use LineParseError::*;
+
+        match self {
+            UnknownLine | MissingPriceUpdate => None,
+            InvalidPriceUpdate(error) => error.token(),
+            InvalidRequest(error) => error.token(),
+            InvalidNumber { token, .. }
+            | InvalidDuration { token, .. }
+            | TimestampError { token, .. }
+            | InvalidCurrency { token, .. }
+            | InvalidExchangeType { token, .. } => Some(token),
+        }
+    }
+
+    /// Gets the column where the offending token starts, if the error is
+    /// about a single field.
+    pub fn column(&self) -> Option<usize> {
+        use LineParseError::*;
+
+        match self {
+            UnknownLine | MissingPriceUpdate => None,
+            InvalidPriceUpdate(error) => error.column(),
+            InvalidRequest(error) => error.column(),
+            InvalidNumber { column, .. }
+            | InvalidDuration { column, .. }
+            | TimestampError { column, .. }
+            | InvalidCurrency { column, .. }
+            | InvalidExchangeType { column, .. } => Some(*column),
+        }
+    }
+}
+
+impl Display for LineParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use LineParseError::*;
+
+        let kind = match self {
+            UnknownLine => return write!(f, "unknown line"),
+            MissingPriceUpdate => return write!(f, "no price update before the line"),
+            InvalidPriceUpdate(error) => return Display::fmt(error, f),
+            InvalidRequest(error) => return Display::fmt(error, f),
+            InvalidNumber { .. } => "number",
+            InvalidDuration { .. } => "duration",
+            TimestampError { .. } => "timestamp",
+            InvalidCurrency { .. } => "currency",
+            InvalidExchangeType { .. } => "exchange",
+        };
+
+        write!(
+            f,
+            "invalid {} `{}` at column {}",
+            kind,
+            self.token().unwrap(),
+            self.column().unwrap()
+        )
+    }
+}
+
+impl Error for LineParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use LineParseError::*;
+
+        match self {
+            UnknownLine | MissingPriceUpdate => None,
+            // Wrapped errors are displayed as they are, so their source is the next cause.
+            InvalidPriceUpdate(error) => error.source(),
+            InvalidRequest(error) => error.source(),
+            InvalidNumber { source, .. } => Some(source),
+            InvalidDuration { source, .. } => Some(source),
+            TimestampError { source, .. } => Some(source),
+            InvalidCurrency { source, .. } => Some(source),
+            InvalidExchangeType { source, .. } => Some(source),
+        }
+    }
+}
+
+impl From<PriceUpdateParseError> for LineParseError {
+    /// Convert from `PriceUpdateParseError` to `LineParseError`.
+    fn from(error: PriceUpdateParseError) -> Self {
+        LineParseError::InvalidPriceUpdate(error)
+    }
+}
+
+impl From<ExchangeRateRequestParseError> for LineParseError {
+    /// Convert from `ExchangeRateRequestParseError` to `LineParseError`.
+    fn from(error: ExchangeRateRequestParseError) -> Self {
+        LineParseError::InvalidRequest(error)
+    }
+}
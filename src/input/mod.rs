@@ -1,5 +1,11 @@
+mod csv;
 mod exchange_rate_request;
+pub(crate) mod line;
+mod order_book;
 mod price_update;
 
+pub use self::csv::*;
 pub use exchange_rate_request::*;
+pub use line::LineParseError;
+pub use order_book::*;
 pub use price_update::*;
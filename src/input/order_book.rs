@@ -0,0 +1,126 @@
+use rust_decimal::Decimal;
+use std::cmp::Reverse;
+
+/// A single level of an order book: an amount (`size`) of the base currency
+/// available at a given `price`, expressed in the quote currency.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PriceLevel {
+    price: Decimal,
+    size: Decimal,
+}
+
+/// A snapshot of the depth of a market, at the time of a price update.
+///
+/// Bids are kept from the highest to the lowest price, and asks from the
+/// lowest to the highest price, so they are always walked best price first.
+#[derive(Debug, Eq, PartialEq, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrderBook {
+    bids: Vec<PriceLevel>,
+    asks: Vec<PriceLevel>,
+}
+
+impl PriceLevel {
+    /// Create a new `PriceLevel` from specified values.
+    pub fn new(price: Decimal, size: Decimal) -> Self {
+        Self { price, size }
+    }
+
+    /// Gets the price of a level, in the quote currency.
+    pub fn price(&self) -> Decimal {
+        self.price
+    }
+
+    /// Gets the size of a level, in the base currency.
+    pub fn size(&self) -> Decimal {
+        self.size
+    }
+}
+
+impl OrderBook {
+    /// Create a new `OrderBook` from specified bid and ask levels, in any order.
+    pub fn new(mut bids: Vec<PriceLevel>, mut asks: Vec<PriceLevel>) -> Self {
+        bids.sort_by_key(|level| Reverse(level.price));
+        asks.sort_by_key(|level| level.price);
+        Self { bids, asks }
+    }
+
+    /// Gets the bid levels, from the highest price.
+    pub fn bids(&self) -> &[PriceLevel] {
+        &self.bids
+    }
+
+    /// Gets the ask levels, from the lowest price.
+    pub fn asks(&self) -> &[PriceLevel] {
+        &self.asks
+    }
+
+    /// Gets the amount of quote currency received for selling `amount` of the
+    /// base currency into the bids, after `consumed` base was already sold.
+    ///
+    /// Returns `None` if the bids are not deep enough.
+    pub fn sell(&self, consumed: Decimal, amount: Decimal) -> Option<Decimal> {
+        walk(
+            &self.bids,
+            consumed,
+            amount,
+            |level| level.size,
+            |level| level.price,
+        )
+    }
+
+    /// Gets the amount of base currency received for spending `amount` of the
+    /// quote currency on the asks, after `consumed` quote was already spent.
+    ///
+    /// Returns `None` if the asks are not deep enough.
+    pub fn buy(&self, consumed: Decimal, amount: Decimal) -> Option<Decimal> {
+        walk(
+            &self.asks,
+            consumed,
+            amount,
+            |level| level.size * level.price,
+            |level| Decimal::from(1) / level.price,
+        )
+    }
+}
+
+/// Walk the levels of one side of a book, skipping the `consumed` input
+/// first, and returns the output received for the next `amount` of input.
+///
+/// `capacity` is the input a level can absorb, and `rate` the output per unit of input.
+fn walk(
+    levels: &[PriceLevel],
+    mut consumed: Decimal,
+    mut amount: Decimal,
+    capacity: impl Fn(&PriceLevel) -> Decimal,
+    rate: impl Fn(&PriceLevel) -> Decimal,
+) -> Option<Decimal> {
+    let zero = Decimal::from(0);
+    let mut received = zero;
+
+    if amount <= zero {
+        return Some(received);
+    }
+
+    for level in levels {
+        let mut available = capacity(level);
+        if consumed >= available {
+            consumed -= available;
+            continue;
+        }
+
+        available -= consumed;
+        consumed = zero;
+
+        let filled = available.min(amount);
+        received += filled * rate(level);
+        amount -= filled;
+
+        if amount <= zero {
+            return Some(received);
+        }
+    }
+
+    None
+}
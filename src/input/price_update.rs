@@ -1,36 +1,100 @@
-use crate::exchange::{
-    Currency, CurrencyParseError, ExchangeType, ExchangeTypeParseError, ExchangeVertex,
-    ExchangeVertexPair,
+use crate::{
+    exchange::{
+        Currency, CurrencyParseError, ExchangeType, ExchangeTypeParseError, ExchangeVertex,
+        ExchangeVertexPair,
+    },
+    input::OrderBook,
+    utils::{
+        str_utils::tokens,
+        time_utils::{parse_timestamp, TIMESTAMP_FORMAT},
+    },
+};
+use chrono::{DateTime, ParseError, Utc};
+use rust_decimal::Decimal;
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    str::FromStr,
 };
-use chrono::{prelude::NaiveDateTime, ParseError};
-use rust_decimal::{Decimal, Error};
-use std::str::FromStr;
 
 /// A representation of a price update.
+///
+/// The forward factor is the rate of selling the source currency, i.e. its
+/// best bid, while the backward factor is the rate of buying it back, i.e. the
+/// inverse of its best ask. The rates are kept as given, whether as factors or
+/// as bid and ask, so the graph quotes every market with its actual spread.
+///
+/// With the `serde` feature, an update is serialized with its bid and ask,
+/// and deserialized with `PriceUpdate::from_bid_ask`. Updates whose ask isn't
+/// positive, or is below their bid, are rejected.
 #[derive(Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "QuotedPriceUpdate", into = "QuotedPriceUpdate")
+)]
 pub struct PriceUpdate {
-    timestamp: NaiveDateTime,
+    timestamp: DateTime<Utc>,
     exchange: ExchangeType,
     source_currency: Currency,
     destination_currency: Currency,
+    bid: Decimal,
+    ask: Decimal,
     backward_factor: Decimal,
+    last: Option<Decimal>,
+    order_book: Option<OrderBook>,
+}
+
+/// The serialized fields of a price update, whose backward factor is derived
+/// from its ask.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct QuotedPriceUpdate {
+    timestamp: DateTime<Utc>,
+    exchange: ExchangeType,
+    source_currency: Currency,
+    destination_currency: Currency,
+    bid: Decimal,
+    ask: Decimal,
+    #[serde(default)]
+    last: Option<Decimal>,
+    #[serde(default)]
+    order_book: Option<OrderBook>,
 }
 
 /// Errors that can occur while parsing a string slice(`&str`) into a given `PriceUpdate`.
+///
+/// Errors about a single field carry the offending token, and the column
+/// (counted in characters, from one) where it starts.
 #[derive(Debug)]
 pub enum PriceUpdateParseError {
-    InvalidFactor,
     IncompleteData,
-    InvalidRate(Error),
-    TimestampError(ParseError),
-    InvalidCurrency(CurrencyParseError),
-    InvalidExchangeType(ExchangeTypeParseError),
+    InvalidRate {
+        token: String,
+        column: usize,
+        source: rust_decimal::Error,
+    },
+    TimestampError {
+        token: String,
+        column: usize,
+        source: ParseError,
+    },
+    InvalidCurrency {
+        token: String,
+        column: usize,
+        source: CurrencyParseError,
+    },
+    InvalidExchangeType {
+        token: String,
+        column: usize,
+        source: ExchangeTypeParseError,
+    },
 }
 
 impl PriceUpdate {
     /// Create a new `PriceUpdate` from the specified values.
     pub fn new(
-        timestamp: NaiveDateTime,
+        timestamp: DateTime<Utc>,
         exchange: ExchangeType,
         source_currency: Currency,
         destination_currency: Currency,
@@ -42,12 +106,80 @@ impl PriceUpdate {
             exchange,
             source_currency,
             destination_currency,
-            backward_factor: backward_factor / forward_factor,
+            bid: forward_factor,
+            ask: inverse(backward_factor),
+            backward_factor,
+            last: None,
+            order_book: None,
+        }
+    }
+
+    /// Create a new `PriceUpdate` from the best bid and ask prices of the
+    /// source currency, in the destination currency.
+    ///
+    /// Selling the source currency yields the bid, while buying it costs the ask.
+    pub fn from_bid_ask(
+        timestamp: DateTime<Utc>,
+        exchange: ExchangeType,
+        source_currency: Currency,
+        destination_currency: Currency,
+        bid: Decimal,
+        ask: Decimal,
+    ) -> Self {
+        Self {
+            timestamp,
+            exchange,
+            source_currency,
+            destination_currency,
+            bid,
+            ask,
+            backward_factor: backward_factor(ask),
+            last: None,
+            order_book: None,
+        }
+    }
+
+    /// Create a new `PriceUpdate` from all of its rates, as written by
+    /// `line::format_price_update`, so that it is restored exactly.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn from_parts(
+        timestamp: DateTime<Utc>,
+        exchange: ExchangeType,
+        source_currency: Currency,
+        destination_currency: Currency,
+        bid: Decimal,
+        ask: Decimal,
+        backward_factor: Decimal,
+    ) -> Self {
+        Self {
+            timestamp,
+            exchange,
+            source_currency,
+            destination_currency,
+            bid,
+            ask,
+            backward_factor,
+            last: None,
+            order_book: None,
         }
     }
 
+    /// Attach the price of the last trade of the market to a price update,
+    /// in the destination currency.
+    pub fn with_last(mut self, last: Decimal) -> Self {
+        self.last = Some(last);
+        self
+    }
+
+    /// Attach the depth of the market to a price update, where the source
+    /// currency is the base and the destination currency is the quote.
+    pub fn with_order_book(mut self, order_book: OrderBook) -> Self {
+        self.order_book = Some(order_book);
+        self
+    }
+
     /// Get the timestamp of a price update.
-    pub fn timestamp(&self) -> &NaiveDateTime {
+    pub fn timestamp(&self) -> &DateTime<Utc> {
         &self.timestamp
     }
 
@@ -68,7 +200,32 @@ impl PriceUpdate {
 
     /// Get the forward factor of a price update.
     pub fn forward_factor(&self) -> Decimal {
-        1.into()
+        self.bid
+    }
+
+    /// Get the best bid of the source currency, in the destination currency.
+    pub fn bid(&self) -> Decimal {
+        self.bid
+    }
+
+    /// Get the best ask of the source currency, in the destination currency.
+    pub fn ask(&self) -> Decimal {
+        self.ask
+    }
+
+    /// Get the mid price between the best bid and ask of the source currency.
+    pub fn mid(&self) -> Decimal {
+        (self.bid() + self.ask()) / Decimal::from(2)
+    }
+
+    /// Get the price of the last trade of the market, if available.
+    pub fn last(&self) -> Option<Decimal> {
+        self.last
+    }
+
+    /// Get the depth of the market of a price update, if available.
+    pub fn order_book(&self) -> Option<&OrderBook> {
+        self.order_book.as_ref()
     }
 
     /// Get the backward factor of a price update.
@@ -88,6 +245,10 @@ impl FromStr for PriceUpdate {
     ///
     /// For example: `2017-11-01T09:42:23+00:00 KRAKEN BTC USD 1000.0 0.0009`
     ///
+    /// The timestamp is either RFC 3339 with any offset or `Z` and optional
+    /// fractional seconds, or Unix epoch seconds or milliseconds. It is stored
+    /// as a UTC instant, so updates from different time zones compare correctly.
+    ///
     /// Example:
     ///
     ///
@@ -95,70 +256,226 @@ impl FromStr for PriceUpdate {
     ///
     /// let sliced_update = "2017-11-01T09:42:23+00:00 KRAKEN BTC USD 1000.0 0.0009";
     /// let output = PriceUpdate::new(
-    ///     NaiveDate::from_ymd(2015, 9, 5).and_hms(23, 56, 4),
+    ///     Utc.ymd(2017, 11, 1).and_hms(9, 42, 23),
     ///     ExchangeType::KRAKEN,
     ///     Currency::BTC,
     ///     Currency::USD,
-    ///     Decimal::from_str("1.0").unwrap(),
-    ///     Decimal::from_str("0.0000009").unwrap(),
+    ///     Decimal::from_str("1000.0").unwrap(),
+    ///     Decimal::from_str("0.0009").unwrap(),
     /// );
     ///
     /// assert_eq!(PriceUpdate::from_str(sliced_update), output);
     ///
     fn from_str(data: &str) -> Result<Self, Self::Err> {
-        // Todo: As an improvement, use `serde` for serialization.
-        let mut values = data.split_whitespace();
+        use PriceUpdateParseError::*;
 
-        if values.clone().count() != 6 {
-            return Err(PriceUpdateParseError::IncompleteData);
+        let values = tokens(data);
+        if values.len() != 6 {
+            return Err(IncompleteData);
         }
 
+        let timestamp = |index: usize| {
+            let (column, token) = values[index];
+            parse_timestamp(token).map_err(|source| TimestampError {
+                token: token.to_string(),
+                column,
+                source,
+            })
+        };
+        let exchange = |index: usize| {
+            let (column, token) = values[index];
+            token.parse().map_err(|source| InvalidExchangeType {
+                token: token.to_string(),
+                column,
+                source,
+            })
+        };
+        let currency = |index: usize| {
+            let (column, token) = values[index];
+            token.parse().map_err(|source| InvalidCurrency {
+                token: token.to_string(),
+                column,
+                source,
+            })
+        };
+        let rate = |index: usize| {
+            let (column, token) = values[index];
+            token.parse().map_err(|source| InvalidRate {
+                token: token.to_string(),
+                column,
+                source,
+            })
+        };
+
         Ok(Self::new(
-            NaiveDateTime::parse_from_str(values.next().unwrap(), "%Y-%m-%dT%H:%M:%S%z")?,
-            values.next().unwrap().parse()?,
-            values.next().unwrap().parse()?,
-            values.next().unwrap().parse()?,
-            values.next().unwrap().parse()?,
-            values.next().unwrap().parse()?,
+            timestamp(0)?,
+            exchange(1)?,
+            currency(2)?,
+            currency(3)?,
+            rate(4)?,
+            rate(5)?,
         ))
     }
 }
 
-impl From<ParseError> for PriceUpdateParseError {
-    /// Convert from `ParseError` to `PriceUpdateParseError`.
-    fn from(error: ParseError) -> Self {
-        PriceUpdateParseError::TimestampError(error)
+impl Display for PriceUpdate {
+    /// Format the price update in the format parsed by `PriceUpdate::from_str`.
+    /// An update created from its bid and ask is written with the inverse of
+    /// its ask as backward factor.
+    ///
+    /// The last trade and order book of the update aren't part of the output.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {} {}",
+            self.timestamp.format(TIMESTAMP_FORMAT),
+            self.exchange,
+            self.source_currency,
+            self.destination_currency,
+            self.bid,
+            self.backward_factor,
+        )
     }
 }
 
-impl From<std::num::ParseFloatError> for PriceUpdateParseError {
-    /// Convert from `std::num::ParseFloatError` to `PriceUpdateParseError`.
-    fn from(_: std::num::ParseFloatError) -> Self {
-        PriceUpdateParseError::InvalidFactor
+impl PriceUpdateParseError {
+    /// Gets the offending token, unless the update is incomplete.
+    pub fn token(&self) -> Option<&str> {
+        use PriceUpdateParseError::*;
+
+        match self {
+            IncompleteData => None,
+            InvalidRate { token, .. }
+            | TimestampError { token, .. }
+            | InvalidCurrency { token, .. }
+            | InvalidExchangeType { token, .. } => Some(token),
+        }
+    }
+
+    /// Gets the column where the offending token starts, unless the update
+    /// is incomplete.
+    pub fn column(&self) -> Option<usize> {
+        use PriceUpdateParseError::*;
+
+        match self {
+            IncompleteData => None,
+            InvalidRate { column, .. }
+            | TimestampError { column, .. }
+            | InvalidCurrency { column, .. }
+            | InvalidExchangeType { column, .. } => Some(*column),
+        }
     }
 }
 
-impl From<CurrencyParseError> for PriceUpdateParseError {
-    /// Convert from `CurrencyParseError` to `PriceUpdateParseError`.
-    fn from(error: CurrencyParseError) -> Self {
-        PriceUpdateParseError::InvalidCurrency(error)
+impl Display for PriceUpdateParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use PriceUpdateParseError::*;
+
+        let kind = match self {
+            IncompleteData => return write!(f, "incomplete price update, expected 6 fields"),
+            InvalidRate { .. } => "rate",
+            TimestampError { .. } => "timestamp",
+            InvalidCurrency { .. } => "currency",
+            InvalidExchangeType { .. } => "exchange",
+        };
+
+        write!(
+            f,
+            "invalid {} `{}` at column {}",
+            kind,
+            self.token().unwrap(),
+            self.column().unwrap()
+        )
     }
 }
 
-impl From<ExchangeTypeParseError> for PriceUpdateParseError {
-    /// Convert from `ExchangeTypeParseError` to `PriceUpdateParseError`.
-    fn from(error: ExchangeTypeParseError) -> Self {
-        PriceUpdateParseError::InvalidExchangeType(error)
+impl Error for PriceUpdateParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        use PriceUpdateParseError::*;
+
+        match self {
+            IncompleteData => None,
+            InvalidRate { source, .. } => Some(source),
+            TimestampError { source, .. } => Some(source),
+            InvalidCurrency { source, .. } => Some(source),
+            InvalidExchangeType { source, .. } => Some(source),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl std::convert::TryFrom<QuotedPriceUpdate> for PriceUpdate {
+    type Error = &'static str;
+
+    /// Convert from `QuotedPriceUpdate` to `PriceUpdate`, rejecting asks which
+    /// aren't positive or are below the bid.
+    fn try_from(quoted: QuotedPriceUpdate) -> Result<Self, Self::Error> {
+        if quoted.ask <= Decimal::from(0) {
+            return Err("ask isn't positive");
+        }
+        if quoted.ask < quoted.bid {
+            return Err("ask is below the bid");
+        }
+
+        let update = PriceUpdate::from_bid_ask(
+            quoted.timestamp,
+            quoted.exchange,
+            quoted.source_currency,
+            quoted.destination_currency,
+            quoted.bid,
+            quoted.ask,
+        );
+
+        Ok(PriceUpdate {
+            last: quoted.last,
+            order_book: quoted.order_book,
+            ..update
+        })
     }
 }
 
-impl From<rust_decimal::Error> for PriceUpdateParseError {
-    /// Convert from `rust_decimal::Error` to `PriceUpdateParseError`.
-    fn from(error: rust_decimal::Error) -> Self {
-        PriceUpdateParseError::InvalidRate(error)
+#[cfg(feature = "serde")]
+impl From<PriceUpdate> for QuotedPriceUpdate {
+    /// Convert from `PriceUpdate` to `QuotedPriceUpdate`.
+    fn from(update: PriceUpdate) -> Self {
+        QuotedPriceUpdate {
+            timestamp: update.timestamp,
+            exchange: update.exchange,
+            source_currency: update.source_currency,
+            destination_currency: update.destination_currency,
+            bid: update.bid,
+            ask: update.ask,
+            last: update.last,
+            order_book: update.order_book,
+        }
     }
 }
 
+/// Gets the inverse of a rate, keeping zero as it is so that invalid updates
+/// can be rejected instead of panicking.
+fn inverse(rate: Decimal) -> Decimal {
+    if rate == Decimal::from(0) {
+        rate
+    } else {
+        Decimal::from(1) / rate
+    }
+}
+
+/// Gets the backward factor of a market from its ask, i.e. its inverse rounded
+/// down, so that buying at the ask and selling back at a bid no higher never
+/// multiplies to more than one.
+fn backward_factor(ask: Decimal) -> Decimal {
+    let mut factor = inverse(ask);
+
+    if ask > Decimal::from(0) {
+        while ask * factor > Decimal::from(1) {
+            factor -= Decimal::new(1, factor.scale());
+        }
+    }
+
+    factor
+}
+
 impl From<&PriceUpdate> for ExchangeVertexPair {
     /// Convert from `CurrencyParseError` to `ExchangeRateRequestParseError`.
     fn from(ex: &PriceUpdate) -> ExchangeVertexPair {
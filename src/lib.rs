@@ -1,25 +1,34 @@
+#[cfg(feature = "adapters")]
+pub mod adapters;
+pub mod backtest;
+mod error;
 pub mod exchange;
-mod input;
+pub mod input;
+#[cfg(feature = "server")]
+pub mod server;
 mod utils;
 
+pub use error::Error;
+
 pub mod prelude {
     //! Basic tools to help make detrermine the best exchange trades and exchange rates.
     //!
     //! It involves:
     //! - Determining a sequence of trades and transfers across exchanges
-    //! to convert the cryptocurrency to fiat currency with a suitable exchange rate.
+    //!   to convert the cryptocurrency to fiat currency with a suitable exchange rate.
     //! - Providing the best possible exchange rate to our customers.
     pub use super::{
-        exchange::{Currency, ExchangeType, RateGraph},
-        input::{ExchangeRateRequest, PriceUpdate},
-        utils::output,
+        exchange::{BestRate, Currency, ExchangeType, RateGraph},
+        input::{ExchangeRateRequest, OrderBook, PriceLevel, PriceUpdate},
+        Error,
     };
-    pub use chrono::NaiveDate;
+    pub use chrono::{DateTime, NaiveDate, TimeZone, Utc};
     pub use rust_decimal::Decimal;
     pub use std::str::FromStr;
 
     pub mod drawing {
         //! A collection of tools for representing graphs in a more readable format.
+        pub use crate::exchange::DotOptions;
         pub use petgraph::dot::{Config, Dot};
     }
 }
@@ -1,54 +1,126 @@
-use exchange_rate::prelude::*;
-
-fn main() {
-    let price_updates = vec![
-        PriceUpdate::new(
-            NaiveDate::from_ymd(2015, 9, 5).and_hms(23, 56, 4),
-            ExchangeType::KRAKEN,
-            Currency::BTC,
-            Currency::USD,
-            Decimal::from_str("1.0").unwrap(),
-            Decimal::from_str("0.0000009").unwrap(),
-        ),
-        PriceUpdate::new(
-            NaiveDate::from_ymd(2015, 9, 5).and_hms(23, 56, 4),
-            ExchangeType::GDAX,
-            Currency::BTC,
-            Currency::USD,
-            Decimal::from_str("1001.0").unwrap(),
-            Decimal::from_str("0.0008").unwrap(),
-        ),
-    ];
-
-    let result = vec![
-        ExchangeRateRequest::new(
-            ExchangeType::KRAKEN,
-            Currency::USD,
-            ExchangeType::GDAX,
-            Currency::BTC,
-        ),
-        ExchangeRateRequest::new(
-            ExchangeType::GDAX,
-            Currency::BTC,
-            ExchangeType::KRAKEN,
-            Currency::USD,
-        ),
-    ];
-
-    let rate_graph = RateGraph::from(price_updates);
-    let mut out = "".to_string();
-
-    for (index, rqst) in result.iter().enumerate() {
-        let (full_path, cost) = rate_graph.full_path(&rqst).unwrap();
-
-        if index == result.len() - 1 {
-            out = format!("{}{}", out, output(&full_path, cost));
-        } else if index == 0 {
-            out = format!("{}{}\n", out, output(&full_path, cost));
+use exchange_rate::{
+    backtest::Backtest,
+    exchange::{discover_symbols, load_registry, LogRecord, PriceLog},
+    prelude::*,
+};
+use std::{
+    env,
+    fs::File,
+    io::{self, BufRead, BufReader},
+    process,
+};
+
+/// Prefix that distinguishes an exchange rate request from a price update.
+const EXCHANGE_RATE_REQUEST: &str = "EXCHANGE_RATE_REQUEST";
+
+/// Reads a mixed stream of price updates and exchange rate requests from stdin.
+///
+/// Price updates are fed into the rate graph as they arrive, while each exchange
+/// rate request is answered with the best rates known at that point in the stream.
+/// Malformed lines are reported on stderr, and the remaining lines are still processed.
+///
+/// Options:
+/// - `--registry <file>`: registers the currencies and exchanges listed in a file.
+/// - `--discover`: registers unknown currencies and exchanges found in the stream.
+/// - `--log <file>`: appends every price update to a log, which can be replayed, before applying it.
+///
+/// `backtest <file>` replays a historical file instead of stdin, and writes a CSV
+/// summary of its requests to stdout, or to the file given by `--output <file>`.
+fn main() -> io::Result<()> {
+    let mut log = None;
+    let mut backtest = None;
+    let mut output = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_ref() {
+            "backtest" => backtest = Some(args.next().unwrap_or_default()),
+            "--discover" => discover_symbols(true),
+            "--log" => log = Some(PriceLog::open(args.next().unwrap_or_default())?),
+            "--output" => output = Some(args.next().unwrap_or_default()),
+            "--registry" => {
+                let path = args.next().unwrap_or_default();
+                let file = BufReader::new(File::open(&path)?);
+
+                if let Err(error) = load_registry(file) {
+                    eprintln!("{}: {}", path, error);
+                    process::exit(1);
+                }
+            }
+            _ => {
+                eprintln!("usage: exchange-rate [--registry <file>] [--discover] [--log <file>]");
+                eprintln!("       exchange-rate backtest <file> [--output <file>] [--registry <file>] [--discover]");
+                process::exit(1);
+            }
+        }
+    }
+
+    if let Some(path) = backtest {
+        let file = BufReader::new(File::open(&path)?);
+        let backtest = match Backtest::run(file, RateGraph::default()) {
+            Ok(backtest) => backtest,
+            Err(error) => {
+                eprintln!("{}: {}", path, error);
+                process::exit(1);
+            }
+        };
+
+        return match output {
+            Some(output) => backtest.write_csv(File::create(output)?),
+            None => backtest.write_csv(io::stdout().lock()),
+        };
+    }
+
+    let stdin = io::stdin();
+    let mut rate_graph = RateGraph::default();
+
+    for (index, line) in stdin.lock().lines().enumerate() {
+        let line = line?;
+        let line_number = index + 1;
+        let data = line.trim();
+
+        if data.is_empty() {
+            continue;
+        }
+
+        if data.starts_with(EXCHANGE_RATE_REQUEST) {
+            let request = match ExchangeRateRequest::from_str(data) {
+                Ok(request) => request,
+                Err(error) => {
+                    eprintln!("line {}: {}", line_number, error);
+                    continue;
+                }
+            };
+
+            match rate_graph.full_path(&request) {
+                Ok(best_rate) => println!("{}", best_rate),
+                Err(error) => eprintln!("line {}: {}", line_number, error),
+            }
         } else {
-            out = format!("{}\n{}", out, output(&full_path, cost));
+            match PriceUpdate::from_str(data) {
+                Ok(update) => {
+                    let records = vec![LogRecord::Update(update)];
+                    if let Some(log) = log.as_mut() {
+                        log.append_records(&records)?;
+                    }
+
+                    let report = rate_graph.apply(records);
+
+                    for (_, reason) in report.rejected() {
+                        eprintln!("line {}: {}", line_number, reason);
+                    }
+
+                    if !report.stale().is_empty() {
+                        eprintln!(
+                            "line {}: price update is older than the last one of its market",
+                            line_number
+                        );
+                    }
+                }
+                Err(error) => eprintln!("line {}: {}", line_number, error),
+            }
         }
     }
 
-    println!("{}", out);
+    Ok(())
 }
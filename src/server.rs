@@ -0,0 +1,251 @@
+//! A JSON over HTTP interface to a shared rate graph.
+//!
+//! Endpoints:
+//! - `POST /prices`: inserts a JSON array of price updates, and responds with
+//!   the `InsertReport` of the batch.
+//! - `GET /rates?from=KRAKEN:USD&to=GDAX:BTC`: responds with the `BestRate`
+//!   between two exchange vertices.
+//!
+//! Errors are reported as `{"error": "<reason>"}` along with a matching status code.
+//! Request bodies larger than `MAX_BODY_SIZE` are rejected with a 413 status code.
+
+use crate::{
+    exchange::{
+        Currency, CurrencyParseError, ExchangeType, ExchangeTypeParseError, LogRecord, PriceLog,
+        RateGraph, RateGraphError, SharedRateGraph,
+    },
+    input::{ExchangeRateRequest, PriceUpdate},
+};
+use serde::Serialize;
+use std::{
+    fs::File,
+    io::{self, Read},
+    net::{SocketAddr, ToSocketAddrs},
+    sync::{Arc, Mutex},
+};
+use tiny_http::{Header, Method, Request, Response};
+
+/// Maximum size of a request body, in bytes.
+pub const MAX_BODY_SIZE: u64 = 1024 * 1024;
+
+/// A response to send back: its status code and JSON body.
+type Reply = (u16, String);
+
+/// An HTTP server answering exchange rate requests from a shared rate graph.
+pub struct Server {
+    http: tiny_http::Server,
+    rate_graph: Arc<SharedRateGraph>,
+    log: Option<Mutex<PriceLog<File>>>,
+}
+
+impl Server {
+    /// Bind a new server to an address, serving the specified rate graph.
+    ///
+    /// Binding to port `0` picks any free port, see `Server::local_addr`.
+    pub fn bind(addr: impl ToSocketAddrs, rate_graph: RateGraph) -> io::Result<Self> {
+        let http =
+            tiny_http::Server::http(addr).map_err(|error| io::Error::other(error.to_string()))?;
+
+        Ok(Self {
+            http,
+            rate_graph: Arc::new(SharedRateGraph::new(rate_graph)),
+            log: None,
+        })
+    }
+
+    /// Append every posted price update to a log, before applying it to the
+    /// rate graph. The log is expected to have been replayed into the rate
+    /// graph the server was bound with.
+    pub fn with_log(mut self, log: PriceLog<File>) -> Self {
+        self.log = Some(Mutex::new(log));
+        self
+    }
+
+    /// Gets the address the server is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.http.server_addr().to_ip().unwrap()
+    }
+
+    /// Gets the rate graph shared by the server.
+    pub fn rate_graph(&self) -> Arc<SharedRateGraph> {
+        Arc::clone(&self.rate_graph)
+    }
+
+    /// Answer incoming requests until the listener fails. Several threads can
+    /// run the same server to answer requests concurrently.
+    ///
+    /// Failing to answer a single request, e.g. because its client disconnected,
+    /// is reported on stderr without stopping the server.
+    pub fn run(&self) -> io::Result<()> {
+        loop {
+            let request = self.http.recv()?;
+            let remote = request.remote_addr().cloned();
+
+            if let Err(error) = self.respond(request) {
+                match remote {
+                    Some(remote) => eprintln!("{}: {}", remote, error),
+                    None => eprintln!("{}", error),
+                }
+            }
+        }
+    }
+
+    /// Route a single request, and send back its response.
+    fn respond(&self, mut request: Request) -> io::Result<()> {
+        let mut body = String::new();
+        let (status, json) = match request
+            .as_reader()
+            .take(MAX_BODY_SIZE + 1)
+            .read_to_string(&mut body)
+        {
+            Ok(size) if size as u64 > MAX_BODY_SIZE => error(413, "request body is too large"),
+            Ok(_) => self.handle(request.method(), request.url(), &body),
+            Err(ref reason) if reason.kind() == io::ErrorKind::InvalidData => {
+                error(400, "request body is not valid UTF-8")
+            }
+            Err(reason) => error(500, &format!("failed to read request body: {}", reason)),
+        };
+
+        let content_type = Header::from_bytes("Content-Type", "application/json").unwrap();
+        let response = Response::from_string(json)
+            .with_status_code(status)
+            .with_header(content_type);
+
+        request.respond(response)
+    }
+
+    /// Handle a request, from its method, url and body.
+    fn handle(&self, method: &Method, url: &str, body: &str) -> Reply {
+        let (path, query) = url.split_once('?').unwrap_or((url, ""));
+
+        match (method, path) {
+            (Method::Post, "/prices") => self.insert_prices(body),
+            (Method::Get, "/rates") => self.best_rate(query),
+            (_, "/prices") | (_, "/rates") => error(405, "method not allowed"),
+            _ => error(404, "not found"),
+        }
+    }
+
+    /// Insert a batch of price updates into the rate graph.
+    fn insert_prices(&self, body: &str) -> Reply {
+        let updates: Vec<PriceUpdate> = match serde_json::from_str(body) {
+            Ok(updates) => updates,
+            Err(reason) => return error(400, &reason.to_string()),
+        };
+
+        // Updates are logged while holding the writer lock, so the log keeps
+        // the order they are applied in.
+        let records: Vec<LogRecord> = updates.into_iter().map(LogRecord::Update).collect();
+        let result: io::Result<_> = self.rate_graph.update(|rate_graph| {
+            if let Some(log) = self.log.as_ref() {
+                log.lock().unwrap().append_records(&records)?;
+            }
+
+            Ok(rate_graph.apply(records))
+        });
+
+        match result {
+            Ok(report) => ok(&report),
+            Err(reason) => error(500, &format!("failed to log price updates: {}", reason)),
+        }
+    }
+
+    /// Find the best rate between the vertices of a query.
+    fn best_rate(&self, query: &str) -> Reply {
+        let request = match parse_request(query) {
+            Ok(request) => request,
+            Err(reason) => return error(400, &reason),
+        };
+
+        match self.rate_graph.full_path(&request) {
+            Ok(best_rate) => ok(&best_rate),
+            Err(reason @ RateGraphError::UnboundedRate(..)) => error(409, &reason.to_string()),
+            Err(reason) => error(404, &reason.to_string()),
+        }
+    }
+}
+
+/// Parse an exchange rate request from a query string, in the form of
+/// `from=<exchange>:<currency>&to=<exchange>:<currency>`.
+fn parse_request(query: &str) -> Result<ExchangeRateRequest, String> {
+    let mut from = None;
+    let mut to = None;
+
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value = percent_decode(value);
+
+        match key {
+            "from" => from = Some(parse_vertex(&value)?),
+            "to" => to = Some(parse_vertex(&value)?),
+            _ => return Err(format!("unknown parameter `{}`", key)),
+        }
+    }
+
+    let (source_exchange, source_currency) = from.ok_or("missing parameter `from`")?;
+    let (destination_exchange, destination_currency) = to.ok_or("missing parameter `to`")?;
+
+    Ok(ExchangeRateRequest::new(
+        source_exchange,
+        source_currency,
+        destination_exchange,
+        destination_currency,
+    ))
+}
+
+/// Parse an exchange vertex in the form of `<exchange>:<currency>`.
+fn parse_vertex(value: &str) -> Result<(ExchangeType, Currency), String> {
+    let (exchange, currency) = value
+        .split_once(':')
+        .ok_or_else(|| format!("`{}` is not in the form <exchange>:<currency>", value))?;
+
+    Ok((
+        exchange
+            .parse()
+            .map_err(|error: ExchangeTypeParseError| error.to_string())?,
+        currency
+            .parse()
+            .map_err(|error: CurrencyParseError| error.to_string())?,
+    ))
+}
+
+/// Decode the `%XX` escapes of a query string value.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match (bytes[i], escaped) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (b'+', _) => {
+                decoded.push(b' ');
+                i += 1;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// A successful reply, with a value serialized as its body.
+fn ok(value: &impl Serialize) -> Reply {
+    (200, serde_json::to_string(value).unwrap())
+}
+
+/// A failed reply, with the reason of the failure.
+fn error(status: u16, reason: &str) -> Reply {
+    (status, serde_json::json!({ "error": reason }).to_string())
+}
@@ -1,4 +1,3 @@
 pub mod map_utils;
-mod output;
-
-pub use output::output;
\ No newline at end of file
+pub mod str_utils;
+pub mod time_utils;
@@ -0,0 +1,23 @@
+/// Split a line into its whitespace separated tokens, along with the column
+/// (counted in characters, from one) where each token starts.
+pub(crate) fn tokens(line: &str) -> Vec<(usize, &str)> {
+    let mut tokens = vec![];
+    let mut start = None;
+
+    for (column, (index, c)) in line.char_indices().enumerate() {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some((column + 1, index)),
+            (Some((column, begin)), true) => {
+                tokens.push((column, &line[begin..index]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    if let Some((column, begin)) = start {
+        tokens.push((column, &line[begin..]));
+    }
+
+    tokens
+}
@@ -0,0 +1,31 @@
+use chrono::{DateTime, ParseError, TimeZone, Utc};
+
+/// Format of the timestamps written by the crate: RFC 3339 in UTC, with
+/// fractional seconds only when there are any.
+pub(crate) const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f+00:00";
+
+/// Epoch timestamps from this magnitude on are in milliseconds rather than
+/// seconds, as seconds would be more than 3000 years away.
+const EPOCH_MILLIS_THRESHOLD: i64 = 100_000_000_000;
+
+/// Parse a timestamp into a UTC instant.
+///
+/// Accepts RFC 3339 timestamps with any offset or `Z`, with or without
+/// fractional seconds, as well as Unix epoch seconds or milliseconds.
+pub(crate) fn parse_timestamp(token: &str) -> Result<DateTime<Utc>, ParseError> {
+    if let Ok(epoch) = token.parse::<i64>() {
+        let instant = if epoch.abs() < EPOCH_MILLIS_THRESHOLD {
+            Utc.timestamp_opt(epoch, 0)
+        } else {
+            Utc.timestamp_millis_opt(epoch)
+        };
+
+        if let Some(instant) = instant.single() {
+            return Ok(instant);
+        }
+    }
+
+    DateTime::parse_from_rfc3339(token)
+        .or_else(|_| DateTime::parse_from_str(token, "%Y-%m-%dT%H:%M:%S%.f%z"))
+        .map(|instant| instant.with_timezone(&Utc))
+}
@@ -0,0 +1,136 @@
+#![cfg(feature = "adapters")]
+
+use exchange_rate::adapters::{coinbase, kraken, AdapterError};
+use exchange_rate::prelude::*;
+use matches::assert_matches;
+use std::fs::read_to_string;
+
+fn bid_ask(
+    timestamp: DateTime<Utc>,
+    exchange: ExchangeType,
+    base: Currency,
+    bid: &str,
+    ask: &str,
+    last: &str,
+) -> PriceUpdate {
+    PriceUpdate::from_bid_ask(
+        timestamp,
+        exchange,
+        base,
+        Currency::USD,
+        Decimal::from_str(bid).unwrap(),
+        Decimal::from_str(ask).unwrap(),
+    )
+    .with_last(Decimal::from_str(last).unwrap())
+}
+
+#[test]
+fn converts_coinbase_tickers() {
+    let fixture = read_to_string("tests/inputs/coinbase/ticker.jsonl").unwrap();
+    let updates: Vec<PriceUpdate> = fixture
+        .lines()
+        .filter_map(|message| coinbase::parse_ticker(message).unwrap())
+        .collect();
+
+    assert_eq!(
+        updates,
+        [
+            bid_ask(
+                Utc.ymd(2017, 11, 1).and_hms_milli(9, 42, 23, 125),
+                ExchangeType::GDAX,
+                Currency::BTC,
+                "6400.99",
+                "6401.00",
+                "6401.00",
+            ),
+            bid_ask(
+                Utc.ymd(2017, 11, 1).and_hms_milli(9, 42, 25, 500),
+                ExchangeType::GDAX,
+                Currency::LTC,
+                "55.01",
+                "55.02",
+                "55.02",
+            ),
+        ]
+    );
+
+    assert_matches!(
+        coinbase::parse_ticker(r#"{"type":"ticker","product_id":"BTC-EUR"}"#),
+        Err(AdapterError::UnknownPair(ref pair)) if pair == "BTC-EUR"
+    );
+    assert_matches!(
+        coinbase::parse_ticker("{"),
+        Err(AdapterError::InvalidJson(_))
+    );
+}
+
+#[test]
+fn converts_kraken_tickers() {
+    let received = Utc.ymd(2017, 11, 1).and_hms(9, 42, 23);
+
+    let fixture = read_to_string("tests/inputs/kraken/ticker.jsonl").unwrap();
+    let updates: Vec<PriceUpdate> = fixture
+        .lines()
+        .flat_map(|message| kraken::parse_ticker(message, received).unwrap())
+        .collect();
+    assert_eq!(
+        updates,
+        [bid_ask(
+            received,
+            ExchangeType::KRAKEN,
+            Currency::BTC,
+            "6400.9",
+            "6401.1",
+            "6401.0",
+        )]
+    );
+
+    let fixture = read_to_string("tests/inputs/kraken/ticker_response.json").unwrap();
+    assert_eq!(
+        kraken::parse_ticker(&fixture, received).unwrap(),
+        [
+            bid_ask(
+                received,
+                ExchangeType::KRAKEN,
+                Currency::LTC,
+                "55.0",
+                "55.03",
+                "55.01",
+            ),
+            bid_ask(
+                received,
+                ExchangeType::KRAKEN,
+                Currency::BTC,
+                "6400.8",
+                "6401.2",
+                "6401.0",
+            ),
+        ]
+    );
+
+    assert_matches!(
+        kraken::parse_ticker(r#"{"error":["EQuery:Unknown asset pair"]}"#, received),
+        Err(AdapterError::ExchangeError(_))
+    );
+}
+
+#[test]
+fn parses_kraken_pair_names() {
+    for pair in &["XBT/USD", "XXBTZUSD", "XBTUSD", "BTC/USD"] {
+        assert_eq!(
+            kraken::parse_pair(pair).unwrap(),
+            (Currency::BTC, Currency::USD),
+            "{}",
+            pair
+        );
+    }
+
+    assert_eq!(
+        kraken::parse_pair("XLTCZUSD").unwrap(),
+        (Currency::LTC, Currency::USD)
+    );
+    assert_matches!(
+        kraken::parse_pair("XXBTZJPY"),
+        Err(AdapterError::UnknownPair(_))
+    );
+}
@@ -0,0 +1,167 @@
+use exchange_rate::exchange::{ExchangeVertex, RateGraphError};
+use exchange_rate::prelude::*;
+use matches::assert_matches;
+
+/// Price updates whose rates multiply to more than one on KRAKEN.
+fn profitable_updates() -> Vec<PriceUpdate> {
+    vec![
+        PriceUpdate::new(
+            Utc.ymd(2017, 11, 1).and_hms(9, 42, 23),
+            ExchangeType::KRAKEN,
+            Currency::BTC,
+            Currency::USD,
+            Decimal::from_str("1000.0").unwrap(),
+            Decimal::from_str("0.002").unwrap(),
+        ),
+        PriceUpdate::new(
+            Utc.ymd(2017, 11, 1).and_hms(9, 43, 23),
+            ExchangeType::GDAX,
+            Currency::BTC,
+            Currency::USD,
+            Decimal::from_str("999.0").unwrap(),
+            Decimal::from_str("0.0009").unwrap(),
+        ),
+    ]
+}
+
+#[test]
+fn finds_no_cycles_without_arbitrage() {
+    let rate_graph = RateGraph::from(vec![PriceUpdate::new(
+        Utc.ymd(2017, 11, 1).and_hms(9, 42, 23),
+        ExchangeType::KRAKEN,
+        Currency::BTC,
+        Currency::USD,
+        Decimal::from_str("1000.0").unwrap(),
+        Decimal::from_str("0.0009").unwrap(),
+    )]);
+
+    assert!(rate_graph.arbitrage_cycles().is_empty());
+}
+
+#[test]
+fn finds_profitable_cycles() {
+    let mut rate_graph = RateGraph::default();
+    rate_graph.insert(profitable_updates());
+
+    let cycles = rate_graph.arbitrage_cycles();
+    assert!(!cycles.is_empty());
+
+    for cycle in cycles {
+        assert!(cycle.gain() > 1.into());
+        assert!(cycle.contains(&ExchangeVertex::new(ExchangeType::KRAKEN, Currency::BTC)));
+        assert!(cycle.contains(&ExchangeVertex::new(ExchangeType::KRAKEN, Currency::USD)));
+    }
+
+    let kraken = cycles
+        .iter()
+        .find(|cycle| cycle.vertices().len() == 2)
+        .unwrap();
+    assert_eq!(
+        kraken.vertices(),
+        &[
+            ExchangeVertex::new(ExchangeType::KRAKEN, Currency::USD),
+            ExchangeVertex::new(ExchangeType::KRAKEN, Currency::BTC),
+        ]
+    );
+    assert_eq!(kraken.gain(), 2.into());
+}
+
+#[test]
+fn rejects_routes_through_profitable_cycles() {
+    let rate_graph = RateGraph::from(profitable_updates());
+    let request = ExchangeRateRequest::new(
+        ExchangeType::GDAX,
+        Currency::USD,
+        ExchangeType::GDAX,
+        Currency::BTC,
+    );
+
+    assert_matches!(
+        rate_graph.full_path(&request),
+        Err(RateGraphError::UnboundedRate(_, _, _))
+    );
+}
+
+#[test]
+fn tracks_cycles_across_inserts() {
+    let update = |minute: u32, exchange: ExchangeType, bid: &str, ask: &str| {
+        PriceUpdate::from_bid_ask(
+            Utc.ymd(2017, 11, 1).and_hms(9, minute, 0),
+            exchange,
+            Currency::BTC,
+            Currency::USD,
+            Decimal::from_str(bid).unwrap(),
+            Decimal::from_str(ask).unwrap(),
+        )
+    };
+    let request = ExchangeRateRequest::new(
+        ExchangeType::GDAX,
+        Currency::USD,
+        ExchangeType::KRAKEN,
+        Currency::BTC,
+    );
+
+    let mut rate_graph = RateGraph::default();
+    rate_graph.insert(vec![
+        update(0, ExchangeType::KRAKEN, "999", "1000"),
+        update(0, ExchangeType::GDAX, "998", "1001"),
+    ]);
+    assert!(rate_graph.arbitrage_cycles().is_empty());
+
+    // Selling on GDAX above the ask of KRAKEN opens a profitable cycle.
+    rate_graph.insert(vec![update(1, ExchangeType::GDAX, "1002", "1003")]);
+    assert!(!rate_graph.arbitrage_cycles().is_empty());
+    assert_matches!(
+        rate_graph.full_path(&request),
+        Err(RateGraphError::UnboundedRate(_, _, _))
+    );
+
+    // Once the cycle closes, the rates match a graph built from scratch.
+    let closed = update(2, ExchangeType::GDAX, "998", "1001");
+    rate_graph.insert(vec![closed.clone()]);
+    assert!(rate_graph.arbitrage_cycles().is_empty());
+
+    let expected = RateGraph::from(vec![update(0, ExchangeType::KRAKEN, "999", "1000"), closed]);
+    assert_eq!(
+        rate_graph.full_path(&request).unwrap(),
+        expected.full_path(&request).unwrap()
+    );
+}
+
+#[test]
+fn finds_cycles_of_tiny_gains() {
+    let update = |exchange: ExchangeType, forward: &str, backward: &str| {
+        PriceUpdate::new(
+            Utc.ymd(2017, 11, 1).and_hms(9, 42, 23),
+            exchange,
+            Currency::BTC,
+            Currency::USD,
+            Decimal::from_str(forward).unwrap(),
+            Decimal::from_str(backward).unwrap(),
+        )
+    };
+
+    // Rates multiplying to exactly one aren't a cycle.
+    let rate_graph = RateGraph::from(vec![update(ExchangeType::GDAX, "1000", "0.001")]);
+    assert!(rate_graph.arbitrage_cycles().is_empty());
+
+    // A gain far below the precision of floating point numbers still is.
+    let rate_graph = RateGraph::from(vec![
+        update(ExchangeType::KRAKEN, "1000", "0.0009"),
+        update(ExchangeType::GDAX, "1000.0000000000001", "0.001"),
+    ]);
+    let cycles = rate_graph.arbitrage_cycles();
+    assert!(!cycles.is_empty());
+    assert!(cycles.iter().all(|cycle| cycle.gain() > 1.into()));
+
+    let request = ExchangeRateRequest::new(
+        ExchangeType::GDAX,
+        Currency::USD,
+        ExchangeType::KRAKEN,
+        Currency::USD,
+    );
+    assert_matches!(
+        rate_graph.full_path(&request),
+        Err(RateGraphError::UnboundedRate(_, _, _))
+    );
+}
@@ -0,0 +1,117 @@
+use chrono::{DateTime, Utc};
+use exchange_rate::exchange::RateGraphError;
+use exchange_rate::prelude::*;
+use matches::assert_matches;
+
+fn at(minute: u32) -> DateTime<Utc> {
+    Utc.ymd(2017, 11, 1).and_hms(9, minute, 0)
+}
+
+fn price_update(minute: u32, exchange: ExchangeType, factor: &str) -> PriceUpdate {
+    PriceUpdate::new(
+        at(minute),
+        exchange,
+        Currency::BTC,
+        Currency::USD,
+        Decimal::from_str("1").unwrap(),
+        Decimal::from_str(factor).unwrap(),
+    )
+}
+
+fn request() -> ExchangeRateRequest {
+    ExchangeRateRequest::new(
+        ExchangeType::KRAKEN,
+        Currency::USD,
+        ExchangeType::KRAKEN,
+        Currency::BTC,
+    )
+}
+
+fn rate_as_of(rate_graph: &RateGraph, minute: u32) -> Decimal {
+    rate_graph
+        .full_path_as_of(&request(), at(minute))
+        .unwrap()
+        .rate()
+}
+
+#[test]
+fn answers_as_of_a_timestamp() {
+    let mut rate_graph = RateGraph::default();
+    rate_graph.insert(vec![
+        price_update(0, ExchangeType::KRAKEN, "0.001"),
+        price_update(10, ExchangeType::KRAKEN, "0.002"),
+        price_update(15, ExchangeType::GDAX, "0.003"),
+        price_update(20, ExchangeType::KRAKEN, "0.004"),
+    ]);
+
+    assert_matches!(
+        rate_graph.full_path_as_of(&request(), Utc.ymd(2017, 10, 1).and_hms(0, 0, 0)),
+        Err(RateGraphError::NoEdgesBetweenNodes(_, _))
+    );
+    assert_eq!(
+        rate_as_of(&rate_graph, 5),
+        Decimal::from_str("0.001").unwrap()
+    );
+    assert_eq!(
+        rate_as_of(&rate_graph, 10),
+        Decimal::from_str("0.002").unwrap()
+    );
+
+    // GDAX was the best route between its update and the next KRAKEN one.
+    let best_rate = rate_graph.full_path_as_of(&request(), at(17)).unwrap();
+    assert_eq!(best_rate.rate(), Decimal::from_str("0.003").unwrap());
+    assert_eq!(best_rate.timestamps(), vec![&at(15)]);
+
+    assert_eq!(
+        rate_as_of(&rate_graph, 30),
+        rate_graph.full_path(&request()).unwrap().rate()
+    );
+}
+
+#[test]
+fn keeps_a_bounded_history() {
+    let mut rate_graph = RateGraph::default();
+    rate_graph.set_history_depth(2);
+    rate_graph.insert(vec![
+        price_update(0, ExchangeType::KRAKEN, "0.001"),
+        price_update(10, ExchangeType::KRAKEN, "0.002"),
+        price_update(20, ExchangeType::KRAKEN, "0.004"),
+    ]);
+
+    assert_matches!(
+        rate_graph.full_path_as_of(&request(), at(5)),
+        Err(RateGraphError::NoEdgesBetweenNodes(_, _))
+    );
+    assert_eq!(
+        rate_as_of(&rate_graph, 15),
+        Decimal::from_str("0.002").unwrap()
+    );
+}
+
+#[test]
+fn keeps_the_history_of_removed_markets() {
+    let mut rate_graph = RateGraph::default();
+    rate_graph.insert(vec![
+        price_update(0, ExchangeType::KRAKEN, "0.001"),
+        price_update(10, ExchangeType::GDAX, "0.002"),
+    ]);
+    rate_graph.remove_market(ExchangeType::KRAKEN, Currency::BTC, Currency::USD);
+    rate_graph.insert(vec![price_update(20, ExchangeType::KRAKEN, "0.004")]);
+
+    // The market is known before its removal, and after it was added back.
+    assert_eq!(
+        rate_as_of(&rate_graph, 5),
+        Decimal::from_str("0.001").unwrap()
+    );
+    assert!(rate_graph.full_path_as_of(&request(), at(15)).is_err());
+    assert_eq!(
+        rate_as_of(&rate_graph, 20),
+        Decimal::from_str("0.004").unwrap()
+    );
+
+    let past = rate_graph.as_of(at(5));
+    assert_eq!(
+        past.full_path(&request()).unwrap(),
+        rate_graph.full_path_as_of(&request(), at(5)).unwrap()
+    );
+}
@@ -0,0 +1,73 @@
+use exchange_rate::backtest::{Backtest, BacktestError};
+use exchange_rate::prelude::*;
+use matches::assert_matches;
+
+const HISTORY: &str = "\
+2017-11-01T09:00:00+00:00 KRAKEN BTC USD 1 0.001
+2017-11-01T09:15:00+00:00 EXCHANGE_RATE_REQUEST KRAKEN USD KRAKEN BTC
+2017-11-01T09:10:00+00:00 KRAKEN BTC USD 1 0.002
+EXCHANGE_RATE_REQUEST KRAKEN USD KRAKEN BTC
+
+2017-11-01T09:20:00+00:00 KRAKEN BTC USD 1 0.003
+EXCHANGE_RATE_REQUEST GDAX USD GDAX BTC
+2017-11-01T09:30:00+00:00 KRAKEN BTC USD 1 0.0015
+";
+
+fn rate(rate: &str) -> Option<Decimal> {
+    Some(Decimal::from_str(rate).unwrap())
+}
+
+#[test]
+fn replays_requests_in_timestamp_order() {
+    let backtest = Backtest::run(HISTORY.as_bytes(), RateGraph::default()).unwrap();
+    let outcomes = backtest.outcomes();
+
+    let lines: Vec<usize> = outcomes.iter().map(|outcome| outcome.line()).collect();
+    assert_eq!(lines, [4, 2, 7]);
+
+    let outcome = &outcomes[1];
+    assert_eq!(
+        outcome.timestamp(),
+        Some(&Utc.ymd(2017, 11, 1).and_hms(9, 15, 0))
+    );
+    assert_eq!(outcome.best_rate().unwrap().rate(), rate("0.002").unwrap());
+    assert_eq!(
+        outcome.evolution(),
+        &[
+            (Utc.ymd(2017, 11, 1).and_hms(9, 20, 0), rate("0.003")),
+            (Utc.ymd(2017, 11, 1).and_hms(9, 30, 0), rate("0.0015")),
+        ]
+    );
+    assert_eq!(outcome.final_rate(), rate("0.0015"));
+    assert_eq!(outcome.min_rate(), rate("0.0015"));
+    assert_eq!(outcome.max_rate(), rate("0.003"));
+
+    assert!(outcomes[2].best_rate().is_err());
+    assert!(outcomes[2].evolution().is_empty());
+}
+
+#[test]
+fn writes_a_csv_summary() {
+    let backtest = Backtest::run(HISTORY.as_bytes(), RateGraph::default()).unwrap();
+
+    let mut csv = vec![];
+    backtest.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let rows: Vec<&str> = csv.lines().collect();
+
+    assert_eq!(
+        rows[0],
+        "line,timestamp,from,to,rate,path,final_rate,min_rate,max_rate,changes,error"
+    );
+    assert_eq!(
+        rows[1],
+        "4,2017-11-01T09:10:00+00:00,KRAKEN:USD,KRAKEN:BTC,0.002,KRAKEN:USD KRAKEN:BTC,0.0015,0.0015,0.003,2,"
+    );
+    assert!(rows[3].starts_with("7,2017-11-01T09:20:00+00:00,GDAX:USD,GDAX:BTC,,,,,,0,\""));
+    assert_eq!(rows.len(), 4);
+
+    assert_matches!(
+        Backtest::run("EXCHANGE_RATE_REQUEST\n".as_bytes(), RateGraph::default()),
+        Err(BacktestError::InvalidLine(1, _))
+    );
+}
@@ -0,0 +1,108 @@
+use exchange_rate::exchange::{ExchangeVertex, UpdateRejection};
+use exchange_rate::prelude::*;
+
+fn btc_usd(exchange: ExchangeType, bid: &str, ask: &str) -> PriceUpdate {
+    PriceUpdate::from_bid_ask(
+        Utc.ymd(2017, 11, 1).and_hms(9, 42, 23),
+        exchange,
+        Currency::BTC,
+        Currency::USD,
+        Decimal::from_str(bid).unwrap(),
+        Decimal::from_str(ask).unwrap(),
+    )
+}
+
+fn request(source: Currency, destination: Currency) -> ExchangeRateRequest {
+    ExchangeRateRequest::new(ExchangeType::GDAX, source, ExchangeType::GDAX, destination)
+}
+
+#[test]
+fn quotes_markets_at_bid_and_ask() {
+    let rate_graph = RateGraph::from(vec![btc_usd(ExchangeType::GDAX, "999", "1000")]);
+
+    // Selling BTC yields the bid, while buying it back costs the ask.
+    let sell = rate_graph
+        .full_path(&request(Currency::BTC, Currency::USD))
+        .unwrap();
+    assert_eq!(sell.rate(), Decimal::from(999));
+
+    let buy = rate_graph
+        .full_path(&request(Currency::USD, Currency::BTC))
+        .unwrap();
+    assert_eq!(buy.rate(), Decimal::from_str("0.001").unwrap());
+
+    // A round trip pays the spread.
+    assert!(sell.rate() * buy.rate() < Decimal::from(1));
+}
+
+#[test]
+fn keeps_quoted_prices() {
+    let update = btc_usd(ExchangeType::GDAX, "6400.99", "6401.01")
+        .with_last(Decimal::from_str("6401.00").unwrap());
+
+    assert_eq!(update.bid(), Decimal::from_str("6400.99").unwrap());
+    assert_eq!(update.ask(), Decimal::from_str("6401.01").unwrap());
+    assert_eq!(update.mid(), Decimal::from_str("6401").unwrap());
+    assert_eq!(update.last(), Some(Decimal::from_str("6401.00").unwrap()));
+    assert_eq!(update.forward_factor(), update.bid());
+
+    // Factors are kept as given, instead of being normalized.
+    let update =
+        PriceUpdate::from_str("2017-11-01T09:42:23+00:00 KRAKEN BTC USD 1000.0 0.0008").unwrap();
+    assert_eq!(update.bid(), Decimal::from_str("1000.0").unwrap());
+    assert_eq!(update.ask(), Decimal::from(1250));
+    assert_eq!(update.last(), None);
+    assert_eq!(
+        update.to_string(),
+        "2017-11-01T09:42:23+00:00 KRAKEN BTC USD 1000.0 0.0008"
+    );
+}
+
+#[test]
+fn rejects_non_positive_prices() {
+    let mut rate_graph = RateGraph::default();
+    let report = rate_graph.insert(vec![
+        btc_usd(ExchangeType::GDAX, "0", "1000"),
+        btc_usd(ExchangeType::KRAKEN, "999", "0"),
+    ]);
+
+    assert!(report.applied().is_empty());
+    assert_eq!(report.rejected().len(), 2);
+    for (_, reason) in report.rejected() {
+        assert_eq!(*reason, UpdateRejection::NonPositiveFactor);
+    }
+}
+
+#[test]
+fn locked_markets_have_no_cycles() {
+    let locked = btc_usd(ExchangeType::GDAX, "6401.07", "6401.07");
+    assert!(locked.bid() * *locked.backward_factor() <= Decimal::from(1));
+
+    let rate_graph = RateGraph::from(vec![locked, btc_usd(ExchangeType::KRAKEN, "6400", "6402")]);
+    assert!(rate_graph.arbitrage_cycles().is_empty());
+
+    let request = ExchangeRateRequest::new(
+        ExchangeType::GDAX,
+        Currency::USD,
+        ExchangeType::KRAKEN,
+        Currency::USD,
+    );
+    assert!(rate_graph.full_path(&request).is_ok());
+
+    // Rounded to the nearest instead, the inverse of the ask made the round
+    // trip profitable, and the best path went around it for ever.
+    let rounded = PriceUpdate::new(
+        Utc.ymd(2017, 11, 1).and_hms(9, 42, 23),
+        ExchangeType::GDAX,
+        Currency::BTC,
+        Currency::USD,
+        Decimal::from_str("6401.07").unwrap(),
+        Decimal::from(1) / Decimal::from_str("6401.07").unwrap(),
+    );
+    let rate_graph = RateGraph::from(vec![rounded, btc_usd(ExchangeType::KRAKEN, "6400", "6402")]);
+    let (u, v) = (
+        ExchangeVertex::new(ExchangeType::GDAX, Currency::USD),
+        ExchangeVertex::new(ExchangeType::KRAKEN, Currency::USD),
+    );
+    assert!(rate_graph.path().full_path(u, v).is_none());
+}
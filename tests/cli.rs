@@ -0,0 +1,48 @@
+use std::{
+    fs::read,
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+/// Run the exchange rate binary, feeding the given input into its stdin.
+fn run(input: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_exchange-rate"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    child.stdin.take().unwrap().write_all(input).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn answers_requests_in_stream_order() {
+    let input = &read("tests/inputs/easy/stream.txt").unwrap();
+    let result = run(input);
+    let stdout = String::from_utf8_lossy(&result.stdout);
+    let file = &read("tests/outputs/easy/output.txt").unwrap();
+    let expected = String::from_utf8_lossy(file);
+
+    assert!(result.status.success());
+    assert_eq!(stdout, format!("{}\n", expected));
+}
+
+#[test]
+fn reports_malformed_lines_without_aborting() {
+    let input = &read("tests/inputs/easy/stream.txt").unwrap();
+    let result = run(input);
+    let stderr = String::from_utf8_lossy(&result.stderr);
+    let errors: Vec<&str> = stderr.lines().collect();
+
+    assert!(result.status.success());
+    assert_eq!(
+        errors,
+        vec![
+            "line 2: no route from <GDAX, BTC> to <GDAX, USD>",
+            "line 4: incomplete price update, expected 6 fields",
+            "line 6: invalid currency `DOGE` at column 28",
+        ]
+    );
+}
@@ -0,0 +1,109 @@
+use exchange_rate::exchange::SharedRateGraph;
+use exchange_rate::prelude::*;
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Barrier},
+    thread,
+};
+
+/// A price update where the rate of buying BTC with USD is `factor` hundredths.
+fn price_update(second: u32, factor: i64) -> PriceUpdate {
+    PriceUpdate::new(
+        Utc.ymd(2017, 11, 1).and_hms(9, 0, second),
+        ExchangeType::KRAKEN,
+        Currency::BTC,
+        Currency::USD,
+        Decimal::from(1),
+        Decimal::new(factor, 2),
+    )
+}
+
+fn request() -> ExchangeRateRequest {
+    ExchangeRateRequest::new(
+        ExchangeType::KRAKEN,
+        Currency::USD,
+        ExchangeType::KRAKEN,
+        Currency::BTC,
+    )
+}
+
+#[test]
+fn snapshots_are_immutable() {
+    let shared = SharedRateGraph::default();
+    shared.insert(vec![price_update(0, 1)]);
+
+    let snapshot = shared.snapshot();
+    shared.insert(vec![price_update(1, 2)]);
+
+    assert_eq!(
+        snapshot.full_path(&request()).unwrap().rate(),
+        Decimal::new(1, 2)
+    );
+    assert_eq!(
+        shared.full_path(&request()).unwrap().rate(),
+        Decimal::new(2, 2)
+    );
+}
+
+#[test]
+fn reads_while_updates_are_applied() {
+    const READERS: usize = 4;
+
+    let shared = Arc::new(SharedRateGraph::default());
+    shared.insert(vec![price_update(0, 1)]);
+
+    // Every write waits for the readers twice while holding the writer lock:
+    // once to let them read, and once more until they all have.
+    let barrier = Arc::new(Barrier::new(READERS + 1));
+    let readers: Vec<_> = (0..READERS)
+        .map(|_| {
+            let shared = Arc::clone(&shared);
+            let barrier = Arc::clone(&barrier);
+            thread::spawn(move || {
+                for second in 1..60 {
+                    barrier.wait();
+                    // The write in progress isn't published yet.
+                    let rate = shared.full_path(&request()).unwrap().rate();
+                    assert_eq!(rate, Decimal::new(i64::from(second), 2));
+                    barrier.wait();
+                }
+            })
+        })
+        .collect();
+
+    for second in 1..60 {
+        shared.update(|rate_graph| {
+            barrier.wait();
+            let report = rate_graph.insert(vec![price_update(second, i64::from(second) + 1)]);
+            barrier.wait();
+            report
+        });
+    }
+
+    for reader in readers {
+        reader.join().unwrap();
+    }
+
+    assert_eq!(
+        shared.full_path(&request()).unwrap().rate(),
+        Decimal::new(60, 2)
+    );
+}
+
+#[test]
+fn keeps_writing_after_a_change_panics() {
+    let shared = SharedRateGraph::default();
+    shared.insert(vec![price_update(0, 1)]);
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        shared.update(|_| panic!("failed change"));
+    }));
+    assert!(result.is_err());
+
+    let report = shared.insert(vec![price_update(1, 2)]);
+    assert_eq!(report.applied().len(), 1);
+    assert_eq!(
+        shared.full_path(&request()).unwrap().rate(),
+        Decimal::new(2, 2)
+    );
+}
@@ -0,0 +1,85 @@
+use exchange_rate::exchange::{ExchangeVertex, RateGraphError, RouteConstraints};
+use exchange_rate::prelude::*;
+use matches::assert_matches;
+
+fn rate_graph() -> RateGraph {
+    RateGraph::from(vec![
+        PriceUpdate::from_str("2017-11-01T09:42:23+00:00 KRAKEN BTC USD 1000.0 0.0009").unwrap(),
+        PriceUpdate::from_str("2017-11-01T09:43:23+00:00 GDAX BTC USD 1001.0 0.0008").unwrap(),
+    ])
+}
+
+fn request() -> ExchangeRateRequest {
+    ExchangeRateRequest::new(
+        ExchangeType::GDAX,
+        Currency::USD,
+        ExchangeType::GDAX,
+        Currency::BTC,
+    )
+}
+
+fn direct_route() -> Vec<ExchangeVertex> {
+    vec![
+        ExchangeVertex::new(ExchangeType::GDAX, Currency::USD),
+        ExchangeVertex::new(ExchangeType::GDAX, Currency::BTC),
+    ]
+}
+
+#[test]
+fn matches_full_path_without_constraints() {
+    let rate_graph = rate_graph();
+    let best_rate = rate_graph
+        .full_path_with(&request(), &RouteConstraints::default())
+        .unwrap();
+
+    assert_eq!(best_rate, rate_graph.full_path(&request()).unwrap());
+    assert_eq!(best_rate.hops().len(), 3);
+}
+
+#[test]
+fn honors_route_constraints() {
+    let rate_graph = rate_graph();
+    let constraints = [
+        RouteConstraints::default().deny_exchange(ExchangeType::KRAKEN),
+        RouteConstraints::default().allow_exchange(ExchangeType::GDAX),
+        RouteConstraints::default().with_max_hops(2),
+        RouteConstraints::default().with_max_transfers(0),
+    ];
+
+    for constraints in constraints.iter() {
+        let best_rate = rate_graph.full_path_with(&request(), constraints).unwrap();
+        assert_eq!(best_rate.vertices(), direct_route());
+    }
+}
+
+#[test]
+fn reports_unsatisfied_constraints() {
+    let rate_graph = rate_graph();
+    let constraints = RouteConstraints::default().deny_currency(Currency::BTC);
+
+    assert_matches!(
+        rate_graph.full_path_with(&request(), &constraints),
+        Err(RateGraphError::UnsatisfiedConstraints(_, _))
+    );
+}
+
+#[test]
+fn ignores_cycles_out_of_reach() {
+    // Buying and selling BTC on KRAKEN doubles any amount.
+    let rate_graph = RateGraph::from(vec![
+        PriceUpdate::from_str("2017-11-01T09:42:23+00:00 KRAKEN BTC USD 1000.0 0.002").unwrap(),
+        PriceUpdate::from_str("2017-11-01T09:43:23+00:00 GDAX BTC USD 999.0 0.0009").unwrap(),
+    ]);
+
+    assert_matches!(
+        rate_graph.full_path_with(&request(), &RouteConstraints::default()),
+        Err(RateGraphError::UnboundedRate(_, _, _))
+    );
+
+    // Going through the cycle on KRAKEN and back to GDAX takes two transfers.
+    for max_transfers in 0..2 {
+        let constraints = RouteConstraints::default().with_max_transfers(max_transfers);
+        let best_rate = rate_graph.full_path_with(&request(), &constraints).unwrap();
+        assert_eq!(best_rate.vertices(), direct_route());
+    }
+}
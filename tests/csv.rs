@@ -0,0 +1,94 @@
+use exchange_rate::input::{CsvError, CsvMapping};
+use exchange_rate::prelude::*;
+use matches::assert_matches;
+
+const EXPORT: &str = "\
+time,venue,base,quote,bid,ask
+2017-11-01T09:42:23Z,KRAKEN,BTC,USD,1000,1250
+2017-11-01T09:43:23Z,GDAX,BTC,USD,abc,1000
+
+1509529463,GDAX,DOGE,USD,1,2
+2017-11-01T09:45:23Z,GDAX,BTC
+\"2017-11-01T09:46:23Z\",\"GDAX\",BTC,USD,\"999\",1000
+";
+
+#[test]
+fn inserts_rows_and_reports_errors() {
+    let mut rate_graph = RateGraph::default();
+    let report = rate_graph
+        .insert_csv(EXPORT.as_bytes(), &CsvMapping::default())
+        .unwrap();
+
+    assert_eq!(report.inserted().applied().len(), 2);
+    let rows: Vec<Option<usize>> = report.errors().iter().map(CsvError::row).collect();
+    assert_eq!(rows, [Some(3), Some(5), Some(6)]);
+    assert_eq!(
+        report.errors()[0].to_string(),
+        "invalid value `abc` in column `bid` of row 3"
+    );
+    assert_matches!(report.errors()[2], CsvError::IncompleteRow(6));
+
+    // Buying BTC with USD costs the ask.
+    let expected = PriceUpdate::new(
+        Utc.ymd(2017, 11, 1).and_hms(9, 42, 23),
+        ExchangeType::KRAKEN,
+        Currency::BTC,
+        Currency::USD,
+        Decimal::from(1000),
+        Decimal::from_str("0.0008").unwrap(),
+    );
+    assert_eq!(report.inserted().applied()[0], expected);
+}
+
+#[test]
+fn maps_configured_columns() {
+    let export = "\
+ts;exchange;from;to;forward;backward
+2017-11-01T09:42:23+00:00;KRAKEN;BTC;USD;1000.0;0.0009
+";
+    let mapping = CsvMapping::default()
+        .with_timestamp_column("ts")
+        .with_exchange_column("Exchange")
+        .with_base_column("from")
+        .with_quote_column("to")
+        .with_factor_columns("forward", "backward")
+        .with_delimiter(';');
+
+    let mut rate_graph = RateGraph::default();
+    let report = rate_graph.insert_csv(export.as_bytes(), &mapping).unwrap();
+    assert_eq!(
+        report.inserted().applied(),
+        &[
+            PriceUpdate::from_str("2017-11-01T09:42:23+00:00 KRAKEN BTC USD 1000.0 0.0009")
+                .unwrap()
+        ]
+    );
+
+    assert_matches!(
+        RateGraph::default().insert_csv(export.as_bytes(), &CsvMapping::default()),
+        Err(CsvError::MissingColumn(ref column)) if column == "time"
+    );
+    assert_matches!(
+        RateGraph::default().insert_csv("".as_bytes(), &mapping),
+        Err(CsvError::MissingHeader)
+    );
+}
+
+#[test]
+fn reads_quoted_line_breaks() {
+    let export = "\
+time,venue,base,quote,bid,ask,note
+2017-11-01T09:42:23Z,KRAKEN,BTC,USD,1000,1250,\"halted,
+then \"\"resumed\"\"\"
+2017-11-01T09:43:23Z,GDAX,BTC,USD,abc,1000,
+";
+
+    let mut rate_graph = RateGraph::default();
+    let report = rate_graph
+        .insert_csv(export.as_bytes(), &CsvMapping::default())
+        .unwrap();
+
+    assert_eq!(report.inserted().applied().len(), 1);
+    let rows: Vec<Option<usize>> = report.errors().iter().map(CsvError::row).collect();
+    assert_eq!(rows, [Some(4)]);
+}
@@ -0,0 +1,75 @@
+use exchange_rate::prelude::drawing::DotOptions;
+use exchange_rate::prelude::*;
+
+fn rate_graph() -> RateGraph {
+    RateGraph::from(vec![
+        PriceUpdate::from_str("2017-11-01T09:42:23+00:00 KRAKEN BTC USD 1000.0 0.0009").unwrap(),
+        PriceUpdate::from_str("2017-11-01T09:43:23+00:00 GDAX BTC USD 1001.0 0.0008").unwrap(),
+    ])
+}
+
+fn request(source: ExchangeType, destination: Currency) -> ExchangeRateRequest {
+    ExchangeRateRequest::new(source, Currency::USD, ExchangeType::KRAKEN, destination)
+}
+
+#[test]
+fn clusters_vertices_by_exchange() {
+    let options = DotOptions::default().with_age_reference(Utc.ymd(2017, 11, 1).and_hms(11, 0, 0));
+
+    assert_eq!(
+        rate_graph().to_dot(&options),
+        r#"digraph {
+    subgraph "cluster_GDAX" {
+        label="GDAX";
+        "GDAX:USD" [label="USD"];
+        "GDAX:BTC" [label="BTC"];
+    }
+    subgraph "cluster_KRAKEN" {
+        label="KRAKEN";
+        "KRAKEN:USD" [label="USD"];
+        "KRAKEN:BTC" [label="BTC"];
+    }
+    "GDAX:USD" -> "GDAX:BTC" [label="0.0008\n1h 16m"];
+    "GDAX:USD" -> "KRAKEN:USD" [label="1", style=dashed];
+    "GDAX:BTC" -> "GDAX:USD" [label="1001.0\n1h 16m"];
+    "GDAX:BTC" -> "KRAKEN:BTC" [label="1", style=dashed];
+    "KRAKEN:USD" -> "GDAX:USD" [label="1", style=dashed];
+    "KRAKEN:USD" -> "KRAKEN:BTC" [label="0.0009\n1h 17m"];
+    "KRAKEN:BTC" -> "GDAX:BTC" [label="1", style=dashed];
+    "KRAKEN:BTC" -> "KRAKEN:USD" [label="1000.0\n1h 17m"];
+}
+"#
+    );
+}
+
+#[test]
+fn highlights_the_route_of_a_request() {
+    let options = DotOptions::default().with_route(request(ExchangeType::GDAX, Currency::BTC));
+    let dot = rate_graph().to_dot(&options);
+
+    assert!(dot.contains("    label=\"GDAX:USD to KRAKEN:BTC: 0.0009\";\n"));
+    let highlighted: Vec<&str> = dot
+        .lines()
+        .filter(|line| line.contains(" -> ") && line.contains("color=red"))
+        .collect();
+    assert_eq!(
+        highlighted,
+        [
+            r#"    "GDAX:USD" -> "KRAKEN:USD" [label="1", style=dashed, color=red, fontcolor=red, penwidth=2];"#,
+            r#"    "KRAKEN:USD" -> "KRAKEN:BTC" [label="0.0009\n1m 0s", color=red, fontcolor=red, penwidth=2];"#,
+        ]
+    );
+
+    // The latest update of the graph is the reference of ages by default.
+    assert!(dot.contains(r#""GDAX:BTC" -> "GDAX:USD" [label="1001.0\n0s"];"#));
+}
+
+#[test]
+fn labels_requests_without_route() {
+    let options = DotOptions::default().with_route(request(ExchangeType::KRAKEN, Currency::LTC));
+    let dot = rate_graph().to_dot(&options);
+
+    assert!(dot.contains("    label=\"no route from <KRAKEN, USD> to <KRAKEN, LTC>\";\n"));
+    assert!(!dot.contains("color=red"));
+    assert!(!dot.contains(r#""KRAKEN:USD" -> "KRAKEN:USD""#));
+}
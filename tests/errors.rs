@@ -0,0 +1,99 @@
+use exchange_rate::backtest::Backtest;
+use exchange_rate::exchange::{CurrencyParseError, RateGraphError};
+use exchange_rate::input::{LineParseError, PriceUpdateParseError};
+use exchange_rate::prelude::*;
+use matches::assert_matches;
+use std::error::Error as _;
+
+#[test]
+fn reports_offending_token_and_column() {
+    let error = PriceUpdate::from_str("2017-11-01T09:42:23+00:00 KRAKEN  DOGE USD 1000.0 0.0009")
+        .unwrap_err();
+
+    assert_eq!(error.token(), Some("DOGE"));
+    assert_eq!(error.column(), Some(35));
+    assert_eq!(error.to_string(), "invalid currency `DOGE` at column 35");
+    assert_eq!(
+        error.source().unwrap().to_string(),
+        "unsupported currency `DOGE`"
+    );
+    assert_matches!(
+        error,
+        PriceUpdateParseError::InvalidCurrency {
+            source: CurrencyParseError::UnsupportedCurrency(ref symbol),
+            ..
+        } if symbol == "DOGE"
+    );
+
+    let error =
+        PriceUpdate::from_str("2017-11-01T09:42:23+00:00 KRAKEN BTC USD 1000.0 lots").unwrap_err();
+    assert_eq!(error.to_string(), "invalid rate `lots` at column 49");
+    assert!(error.source().is_some());
+
+    let error =
+        ExchangeRateRequest::from_str("EXCHANGE_RATE_REQUEST GDAX BTC BINANCE USD").unwrap_err();
+    assert_eq!(error.to_string(), "invalid exchange `BINANCE` at column 32");
+}
+
+#[test]
+fn wraps_every_error_into_the_crate_error() {
+    fn best_rate(rate_graph: &RateGraph, request: &str) -> Result<BestRate, Error> {
+        let request = ExchangeRateRequest::from_str(request)?;
+        Ok(rate_graph.full_path(&request)?)
+    }
+
+    let rate_graph = RateGraph::from(vec![PriceUpdate::from_str(
+        "2017-11-01T09:42:23+00:00 KRAKEN BTC USD 1000.0 0.0009",
+    )
+    .unwrap()]);
+
+    assert!(best_rate(&rate_graph, "EXCHANGE_RATE_REQUEST KRAKEN USD KRAKEN BTC").is_ok());
+
+    let error = best_rate(&rate_graph, "EXCHANGE_RATE_REQUEST KRAKEN USD GDAX BTC").unwrap_err();
+    assert_matches!(
+        error,
+        Error::RateGraph(RateGraphError::NoEdgesBetweenNodes(_, _))
+    );
+    assert_eq!(
+        error.to_string(),
+        "no route from <KRAKEN, USD> to <GDAX, BTC>"
+    );
+
+    let error = best_rate(&rate_graph, "EXCHANGE_RATE_REQUEST KRAKEN DOGE GDAX BTC").unwrap_err();
+    assert_eq!(error.to_string(), "invalid currency `DOGE` at column 30");
+    assert_eq!(
+        error.source().unwrap().to_string(),
+        "unsupported currency `DOGE`"
+    );
+}
+
+#[test]
+fn chains_the_errors_of_invalid_lines() {
+    let snapshot = "RATE_GRAPH_SNAPSHOT 2\nTRADING_FEE KRAKEN lots\n";
+    let error = RateGraph::load_snapshot(snapshot.as_bytes()).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid snapshot entry on line 2: invalid number `lots` at column 20"
+    );
+
+    let line = error.source().unwrap();
+    assert_matches!(
+        line.downcast_ref::<LineParseError>(),
+        Some(error) if error.token() == Some("lots") && error.column() == Some(20)
+    );
+    assert!(line.source().is_some());
+
+    let error = Backtest::run(
+        "2017-11-01T09:42:23+00:00 KRAKEN DOGE USD 1000.0 0.0009\n".as_bytes(),
+        RateGraph::default(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid backtest line 1: invalid currency `DOGE` at column 34"
+    );
+    assert_eq!(
+        error.source().unwrap().source().unwrap().to_string(),
+        "unsupported currency `DOGE`"
+    );
+}
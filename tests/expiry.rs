@@ -0,0 +1,95 @@
+use chrono::Duration;
+use exchange_rate::exchange::RateGraphError;
+use exchange_rate::prelude::*;
+use matches::assert_matches;
+
+fn price_update(minute: u32, exchange: ExchangeType, factor: &str) -> PriceUpdate {
+    PriceUpdate::new(
+        Utc.ymd(2017, 11, 1).and_hms(9, minute, 0),
+        exchange,
+        Currency::BTC,
+        Currency::USD,
+        Decimal::from_str("1").unwrap(),
+        Decimal::from_str(factor).unwrap(),
+    )
+}
+
+fn request() -> ExchangeRateRequest {
+    ExchangeRateRequest::new(
+        ExchangeType::KRAKEN,
+        Currency::USD,
+        ExchangeType::KRAKEN,
+        Currency::BTC,
+    )
+}
+
+#[test]
+fn expires_markets_before_cutoff() {
+    let mut rate_graph = RateGraph::default();
+    rate_graph.insert(vec![
+        price_update(0, ExchangeType::KRAKEN, "0.002"),
+        price_update(10, ExchangeType::GDAX, "0.001"),
+    ]);
+
+    let expired = rate_graph.expire_before(Utc.ymd(2017, 11, 1).and_hms(9, 5, 0));
+    assert_eq!(expired.len(), 2);
+    assert_eq!(rate_graph.expired_markets().len(), 2);
+
+    // KRAKEN USD can still reach KRAKEN BTC through GDAX.
+    let best_rate = rate_graph.full_path(&request()).unwrap();
+    assert_eq!(best_rate.rate(), Decimal::from_str("0.001").unwrap());
+
+    rate_graph.expire_before(Utc.ymd(2017, 11, 1).and_hms(9, 15, 0));
+    assert_matches!(
+        rate_graph.full_path(&request()),
+        Err(RateGraphError::StaleRate(_, _))
+    );
+}
+
+#[test]
+fn expires_markets_older_than_max_age() {
+    let mut rate_graph = RateGraph::default();
+    rate_graph.set_max_age(Some(Duration::minutes(5)));
+
+    rate_graph.insert(vec![price_update(0, ExchangeType::KRAKEN, "0.002")]);
+    assert!(rate_graph.full_path(&request()).is_ok());
+
+    rate_graph.insert(vec![price_update(10, ExchangeType::GDAX, "0.001")]);
+    let best_rate = rate_graph.full_path(&request()).unwrap();
+    assert_eq!(best_rate.rate(), Decimal::from_str("0.001").unwrap());
+
+    // A fresh update revives the market.
+    rate_graph.insert(vec![price_update(11, ExchangeType::KRAKEN, "0.002")]);
+    assert!(rate_graph.expired_markets().is_empty());
+    let best_rate = rate_graph.full_path(&request()).unwrap();
+    assert_eq!(best_rate.rate(), Decimal::from_str("0.002").unwrap());
+}
+
+#[test]
+fn reports_stale_rates_only_for_expired_markets() {
+    let mut rate_graph = RateGraph::default();
+    rate_graph.insert(vec![
+        price_update(0, ExchangeType::KRAKEN, "0.002"),
+        price_update(10, ExchangeType::GDAX, "0.001"),
+    ]);
+
+    let unknown = ExchangeRateRequest::new(
+        ExchangeType::KRAKEN,
+        Currency::USD,
+        ExchangeType::KRAKEN,
+        Currency::LTC,
+    );
+    assert!(rate_graph.expired_markets().is_empty());
+    assert_matches!(
+        rate_graph.full_path(&unknown),
+        Err(RateGraphError::NoEdgesBetweenNodes(_, _))
+    );
+
+    // Markets which expired elsewhere don't make an unknown market stale.
+    rate_graph.expire_before(Utc.ymd(2017, 11, 1).and_hms(9, 5, 0));
+    assert_matches!(
+        rate_graph.full_path(&unknown),
+        Err(RateGraphError::NoEdgesBetweenNodes(_, _))
+    );
+    assert!(rate_graph.full_path(&request()).is_ok());
+}
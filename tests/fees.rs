@@ -0,0 +1,90 @@
+use exchange_rate::exchange::FeeModel;
+use exchange_rate::prelude::*;
+
+fn price_updates() -> Vec<PriceUpdate> {
+    vec![
+        PriceUpdate::new(
+            Utc.ymd(2017, 11, 1).and_hms(9, 42, 23),
+            ExchangeType::KRAKEN,
+            Currency::BTC,
+            Currency::USD,
+            Decimal::from_str("1000.0").unwrap(),
+            Decimal::from_str("0.0009").unwrap(),
+        ),
+        PriceUpdate::new(
+            Utc.ymd(2017, 11, 1).and_hms(9, 43, 23),
+            ExchangeType::GDAX,
+            Currency::BTC,
+            Currency::USD,
+            Decimal::from_str("1001.0").unwrap(),
+            Decimal::from_str("0.0008").unwrap(),
+        ),
+    ]
+}
+
+fn fees() -> FeeModel {
+    FeeModel::default()
+        .with_trading_fee(ExchangeType::KRAKEN, Decimal::from_str("0.01").unwrap())
+        .with_withdrawal_fee(Currency::USD, Decimal::from_str("5").unwrap())
+        .with_transfer_factor(
+            ExchangeType::GDAX,
+            ExchangeType::KRAKEN,
+            Decimal::from_str("0.5").unwrap(),
+        )
+}
+
+#[test]
+fn applies_fees_to_rates() {
+    let mut rate_graph = RateGraph::with_fees(fees());
+    rate_graph.insert(price_updates());
+
+    let request = ExchangeRateRequest::new(
+        ExchangeType::GDAX,
+        Currency::USD,
+        ExchangeType::KRAKEN,
+        Currency::BTC,
+    );
+
+    let best_rate = rate_graph.full_path(&request).unwrap();
+    let hops = best_rate.hops();
+
+    assert_eq!(hops[0].rate(), Decimal::from_str("0.5").unwrap());
+    assert_eq!(hops[1].rate(), Decimal::from_str("0.000891").unwrap());
+    assert_eq!(best_rate.rate(), Decimal::from_str("0.0004455").unwrap());
+}
+
+#[test]
+fn deducts_withdrawal_fees_from_amounts() {
+    let mut rate_graph = RateGraph::with_fees(fees());
+    rate_graph.insert(price_updates());
+
+    let request = ExchangeRateRequest::new(
+        ExchangeType::KRAKEN,
+        Currency::USD,
+        ExchangeType::GDAX,
+        Currency::USD,
+    );
+
+    let best_rate = rate_graph.full_path(&request).unwrap();
+    assert_eq!(best_rate.hops()[0].fee(), 5.into());
+    assert_eq!(best_rate.net_amount(105.into()), 100.into());
+    assert_eq!(best_rate.net_amount(3.into()), 0.into());
+}
+
+#[test]
+fn keeps_rates_without_fees() {
+    let rate_graph = RateGraph::from(price_updates());
+    let request = ExchangeRateRequest::new(
+        ExchangeType::GDAX,
+        Currency::USD,
+        ExchangeType::KRAKEN,
+        Currency::BTC,
+    );
+
+    let best_rate = rate_graph.full_path(&request).unwrap();
+    assert_eq!(best_rate.rate(), Decimal::from_str("0.0009").unwrap());
+    assert_eq!(
+        best_rate.net_amount(2.into()),
+        Decimal::from_str("0.0018").unwrap()
+    );
+}
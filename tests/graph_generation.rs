@@ -1,12 +1,11 @@
-use exchange_rate::exchange::{ExchangeVertex, RateGraphError};
+use exchange_rate::exchange::{ExchangeVertex, HopKind, RateGraphError};
 use exchange_rate::prelude::*;
 use matches::assert_matches;
-use num_traits::cast::FromPrimitive;
 
 fn price_updates() -> Vec<PriceUpdate> {
     vec![
         PriceUpdate::new(
-            NaiveDate::from_ymd(2017, 11, 1).and_hms(9, 42, 23),
+            Utc.ymd(2017, 11, 1).and_hms(9, 42, 23),
             ExchangeType::KRAKEN,
             Currency::BTC,
             Currency::USD,
@@ -14,7 +13,7 @@ fn price_updates() -> Vec<PriceUpdate> {
             Decimal::from_str("0.0009").unwrap(),
         ),
         PriceUpdate::new(
-            NaiveDate::from_ymd(2016, 11, 1).and_hms(1, 23, 23),
+            Utc.ymd(2016, 11, 1).and_hms(1, 23, 23),
             ExchangeType::GDAX,
             Currency::BTC,
             Currency::USD,
@@ -51,7 +50,7 @@ fn test_generated_graph() {
 fn test_shortest_path_cost_correctness() {
     let rate_graph = RateGraph::from(price_updates());
 
-    let requests = vec![
+    let requests = [
         ExchangeRateRequest::new(
             ExchangeType::GDAX,
             Currency::USD,
@@ -66,10 +65,10 @@ fn test_shortest_path_cost_correctness() {
         ),
     ];
 
-    let expected_rate = vec![0.000_001_8, 1.0];
+    let expected_rate = vec!["0.0009", "1001.0"];
     for (rqst, rate) in requests.iter().zip(expected_rate) {
-        let (_, cost) = rate_graph.full_path(&rqst).unwrap();
-        assert_eq!(cost, Decimal::from_f32(rate).unwrap());
+        let best_rate = rate_graph.full_path(rqst).unwrap();
+        assert_eq!(best_rate.rate(), Decimal::from_str(rate).unwrap());
     }
 }
 
@@ -77,7 +76,7 @@ fn test_shortest_path_cost_correctness() {
 fn test_generated_path_correctness() {
     let rate_graph = RateGraph::from(price_updates());
 
-    let request = vec![
+    let request = [
         ExchangeRateRequest::new(
             ExchangeType::KRAKEN,
             Currency::USD,
@@ -94,7 +93,7 @@ fn test_generated_path_correctness() {
 
     let path_a = vec![
         ExchangeVertex::new(ExchangeType::GDAX, Currency::BTC),
-        ExchangeVertex::new(ExchangeType::KRAKEN, Currency::BTC),
+        ExchangeVertex::new(ExchangeType::GDAX, Currency::USD),
         ExchangeVertex::new(ExchangeType::KRAKEN, Currency::USD),
     ];
 
@@ -105,10 +104,36 @@ fn test_generated_path_correctness() {
     );
 
     let generated_path = rate_graph.full_path(&request[1]).unwrap();
-    for (input, output) in generated_path.0.iter().zip(path_a) {
+    for (input, output) in generated_path.vertices().iter().zip(path_a) {
         assert_eq!(input.currency(), output.currency());
         assert_eq!(input.exchange(), output.exchange());
     }
 
-    assert_eq!(generated_path.1, 2.into());
+    assert_eq!(generated_path.rate(), Decimal::from_str("1001.0").unwrap());
+}
+
+#[test]
+fn test_best_rate_hops() {
+    let rate_graph = RateGraph::from(price_updates());
+    let request = ExchangeRateRequest::new(
+        ExchangeType::GDAX,
+        Currency::USD,
+        ExchangeType::KRAKEN,
+        Currency::BTC,
+    );
+
+    let best_rate = rate_graph.full_path(&request).unwrap();
+    let hops = best_rate.hops();
+
+    assert_eq!(best_rate.request(), &request);
+    assert_eq!(hops.len(), 2);
+    assert_eq!(hops[0].kind(), HopKind::Transfer);
+    assert_eq!(hops[0].rate(), 1.into());
+    assert_eq!(hops[0].timestamp(), None);
+    assert_eq!(hops[1].kind(), HopKind::Trade);
+    assert_eq!(hops[1].rate(), Decimal::from_str("0.0009").unwrap());
+    assert_eq!(
+        best_rate.timestamps(),
+        vec![&Utc.ymd(2017, 11, 1).and_hms(9, 42, 23)]
+    );
 }
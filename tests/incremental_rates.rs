@@ -0,0 +1,96 @@
+use exchange_rate::exchange::{ExchangeVertex, Path};
+use exchange_rate::prelude::*;
+
+const EXCHANGES: [ExchangeType; 2] = [ExchangeType::GDAX, ExchangeType::KRAKEN];
+const CURRENCIES: [Currency; 3] = [Currency::USD, Currency::BTC, Currency::LTC];
+/// Reference prices of the currencies, in USD.
+const PRICES: [&str; 3] = ["1", "8", "2"];
+const RATIOS: [&str; 6] = ["1", "0.5", "0.25", "0.8", "0.1", "0.05"];
+
+/// A tiny xorshift generator, so the randomized updates are reproducible.
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % bound as u64) as usize
+    }
+}
+
+/// Generate a random price update, without ever creating a profitable cycle.
+///
+/// Both factors are at most the ratio of the reference prices, so the rates of
+/// any cycle multiply to at most one.
+fn random_update(random: &mut Random, second: u32) -> PriceUpdate {
+    let source = random.next(CURRENCIES.len());
+    let destination = (source + 1 + random.next(CURRENCIES.len() - 1)) % CURRENCIES.len();
+    let price = |currency: usize| Decimal::from_str(PRICES[currency]).unwrap();
+    let mut ratio = || Decimal::from_str(RATIOS[random.next(RATIOS.len())]).unwrap();
+    let forward_factor = price(source) / price(destination) * ratio();
+    let backward_factor = price(destination) / price(source) * ratio();
+
+    PriceUpdate::new(
+        Utc.ymd(2017, 11, 1).and_hms(9, second / 60, second % 60),
+        EXCHANGES[random.next(EXCHANGES.len())],
+        CURRENCIES[source],
+        CURRENCIES[destination],
+        forward_factor,
+        backward_factor,
+    )
+}
+
+/// Assert that the incrementally maintained paths match a full recalculation,
+/// and that following the stored path yields the stored rate.
+fn assert_matches_full_recalculation(rate_graph: &RateGraph) {
+    let graph = rate_graph.graph();
+    let mut expected = Path::default();
+    expected.floyd_warshall(graph, 0);
+
+    for u in graph.nodes() {
+        for v in graph.nodes() {
+            let rate = rate_graph.path().rate(u, v);
+            assert_eq!(rate, expected.rate(u, v), "rate from {:?} to {:?}", u, v);
+
+            if u == v || rate_graph.path().next(u, v).is_none() {
+                continue;
+            }
+
+            let mut node: ExchangeVertex = u;
+            let mut product = Decimal::from(1);
+            while node != v {
+                let hop = rate_graph.path().next(node, v).unwrap();
+                product *= *graph.edge_weight(node, hop).unwrap();
+                node = hop;
+            }
+
+            assert_eq!(Some(&product), rate, "path from {:?} to {:?}", u, v);
+        }
+    }
+}
+
+#[test]
+fn incremental_rates_match_full_recalculation() {
+    for seed in 1..=20u64 {
+        let mut random = Random(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        let mut rate_graph = RateGraph::default();
+
+        for second in 0..60 {
+            rate_graph.insert(vec![random_update(&mut random, second)]);
+            assert_matches_full_recalculation(&rate_graph);
+        }
+    }
+}
+
+#[test]
+fn batched_insert_matches_full_recalculation() {
+    let mut random = Random(42);
+    let mut rate_graph = RateGraph::default();
+
+    for batch in 0..10 {
+        let updates = (0..5).map(|index| random_update(&mut random, batch * 5 + index));
+        rate_graph.insert(updates.collect::<Vec<_>>());
+        assert_matches_full_recalculation(&rate_graph);
+    }
+}
@@ -5,7 +5,7 @@ use std::fs::read;
 fn price_updates() -> Vec<PriceUpdate> {
     vec![
         PriceUpdate::new(
-            NaiveDate::from_ymd(2017, 11, 1).and_hms(9, 42, 23),
+            Utc.ymd(2017, 11, 1).and_hms(9, 42, 23),
             ExchangeType::KRAKEN,
             Currency::BTC,
             Currency::USD,
@@ -13,7 +13,7 @@ fn price_updates() -> Vec<PriceUpdate> {
             Decimal::from_str("0.0009").unwrap(),
         ),
         PriceUpdate::new(
-            NaiveDate::from_ymd(2016, 11, 1).and_hms(1, 23, 23),
+            Utc.ymd(2016, 11, 1).and_hms(1, 23, 23),
             ExchangeType::GDAX,
             Currency::BTC,
             Currency::USD,
@@ -35,14 +35,14 @@ fn output_is_correct() {
     let mut out = "".to_string();
     for (index, rqst) in rate_requests.lines().enumerate() {
         let request = ExchangeRateRequest::from_str(rqst).unwrap();
-        let (full_path, cost) = rate_graph.full_path(&request).unwrap();
+        let best_rate = rate_graph.full_path(&request).unwrap();
 
         if index == rate_requests.lines().count() - 1 {
-            out = format!("{}{}", out, output(&full_path, cost));
+            out = format!("{}{}", out, best_rate);
         } else if index == 0 {
-            out = format!("{}{}\n", out, output(&full_path, cost));
+            out = format!("{}{}\n", out, best_rate);
         } else {
-            out = format!("{}\n{}", out, output(&full_path, cost));
+            out = format!("{}\n{}", out, best_rate);
         }
     }
 
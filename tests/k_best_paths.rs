@@ -0,0 +1,74 @@
+use exchange_rate::exchange::{ExchangeVertex, RateGraphError};
+use exchange_rate::prelude::*;
+use matches::assert_matches;
+
+fn price_updates() -> Vec<PriceUpdate> {
+    vec![
+        PriceUpdate::from_str("2017-11-01T09:42:23+00:00 KRAKEN BTC USD 1000.0 0.0009").unwrap(),
+        PriceUpdate::from_str("2017-11-01T09:43:23+00:00 GDAX BTC USD 1001.0 0.0008").unwrap(),
+        PriceUpdate::from_str("2017-11-01T09:44:23+00:00 GDAX LTC USD 100.0 0.001").unwrap(),
+        PriceUpdate::from_str("2017-11-01T09:45:23+00:00 GDAX BTC LTC 10.0 0.05").unwrap(),
+    ]
+}
+
+fn request() -> ExchangeRateRequest {
+    ExchangeRateRequest::new(
+        ExchangeType::GDAX,
+        Currency::USD,
+        ExchangeType::KRAKEN,
+        Currency::BTC,
+    )
+}
+
+#[test]
+fn ranks_loop_free_routes() {
+    let rate_graph = RateGraph::from(price_updates());
+    let best_rates = rate_graph.k_best_paths(&request(), 3).unwrap();
+
+    assert_eq!(best_rates.len(), 3);
+    assert_eq!(
+        best_rates[0].rate(),
+        rate_graph.full_path(&request()).unwrap().rate()
+    );
+
+    for pair in best_rates.windows(2) {
+        assert!(pair[0].rate() >= pair[1].rate());
+        assert_ne!(pair[0].vertices(), pair[1].vertices());
+    }
+
+    for best_rate in best_rates.iter() {
+        let vertices: Vec<ExchangeVertex> = best_rate.vertices();
+        let mut unique = vertices.clone();
+        unique.sort();
+        unique.dedup();
+
+        assert_eq!(unique.len(), vertices.len());
+        assert_eq!(
+            vertices.first(),
+            Some(&ExchangeVertex::new(ExchangeType::GDAX, Currency::USD))
+        );
+        assert_eq!(
+            vertices.last(),
+            Some(&ExchangeVertex::new(ExchangeType::KRAKEN, Currency::BTC))
+        );
+    }
+}
+
+#[test]
+fn returns_fewer_routes_when_no_more_exist() {
+    let update =
+        PriceUpdate::from_str("2017-11-01T09:42:23+00:00 KRAKEN BTC USD 1000.0 0.0009").unwrap();
+    let rate_graph = RateGraph::from(vec![update]);
+    let direct = ExchangeRateRequest::new(
+        ExchangeType::KRAKEN,
+        Currency::USD,
+        ExchangeType::KRAKEN,
+        Currency::BTC,
+    );
+
+    assert_eq!(rate_graph.k_best_paths(&direct, 3).unwrap().len(), 1);
+    assert_matches!(
+        rate_graph.k_best_paths(&request(), 3),
+        Err(RateGraphError::NoEdgesBetweenNodes(_, _))
+    );
+}
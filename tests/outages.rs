@@ -0,0 +1,66 @@
+use exchange_rate::exchange::{ExchangeVertex, RateGraphError};
+use exchange_rate::prelude::*;
+use matches::assert_matches;
+
+fn price_updates() -> Vec<PriceUpdate> {
+    vec![
+        PriceUpdate::from_str("2017-11-01T09:42:23+00:00 KRAKEN BTC USD 1000.0 0.0009").unwrap(),
+        PriceUpdate::from_str("2017-11-01T09:43:23+00:00 GDAX BTC USD 1001.0 0.0008").unwrap(),
+    ]
+}
+
+fn request(source: ExchangeType, destination: ExchangeType) -> ExchangeRateRequest {
+    ExchangeRateRequest::new(source, Currency::USD, destination, Currency::BTC)
+}
+
+#[test]
+fn removes_markets() {
+    let mut rate_graph = RateGraph::default();
+    rate_graph.insert(price_updates());
+
+    assert!(rate_graph.remove_market(ExchangeType::KRAKEN, Currency::BTC, Currency::USD));
+    assert!(!rate_graph.remove_market(ExchangeType::KRAKEN, Currency::BTC, Currency::USD));
+
+    let kraken_btc = ExchangeVertex::new(ExchangeType::KRAKEN, Currency::BTC);
+    assert!(!rate_graph.graph().contains_node(kraken_btc));
+
+    let gdax = request(ExchangeType::GDAX, ExchangeType::GDAX);
+    let expected = RateGraph::from(vec![price_updates().remove(1)]);
+    assert_eq!(
+        rate_graph.full_path(&gdax).unwrap(),
+        expected.full_path(&gdax).unwrap()
+    );
+
+    assert_matches!(
+        rate_graph.full_path(&request(ExchangeType::KRAKEN, ExchangeType::GDAX)),
+        Err(RateGraphError::NoEdgesBetweenNodes(_, _))
+    );
+}
+
+#[test]
+fn suspends_and_resumes_exchanges() {
+    let mut rate_graph = RateGraph::default();
+    rate_graph.insert(price_updates());
+
+    let gdax = request(ExchangeType::GDAX, ExchangeType::GDAX);
+    let through_kraken = rate_graph.full_path(&gdax).unwrap();
+    assert_eq!(through_kraken.hops().len(), 3);
+
+    rate_graph.suspend_exchange(ExchangeType::KRAKEN);
+    assert_eq!(rate_graph.full_path(&gdax).unwrap().hops().len(), 1);
+    assert_matches!(
+        rate_graph.full_path(&request(ExchangeType::KRAKEN, ExchangeType::GDAX)),
+        Err(RateGraphError::NoEdgesBetweenNodes(_, _))
+    );
+
+    // Updates received during the outage are applied once the exchange resumes.
+    let update =
+        PriceUpdate::from_str("2017-11-01T09:44:23+00:00 KRAKEN BTC USD 1000.0 0.00095").unwrap();
+    rate_graph.insert(vec![update]);
+    assert_eq!(rate_graph.full_path(&gdax).unwrap().hops().len(), 1);
+
+    rate_graph.resume_exchange(ExchangeType::KRAKEN);
+    let best_rate = rate_graph.full_path(&gdax).unwrap();
+    assert_eq!(best_rate.hops().len(), 3);
+    assert_eq!(best_rate.rate(), Decimal::from_str("0.00095").unwrap());
+}
@@ -0,0 +1,232 @@
+use chrono::Duration;
+use exchange_rate::exchange::{FeeModel, LogError, LogRecord, PriceLog, LOG_VERSION};
+use exchange_rate::prelude::*;
+use matches::assert_matches;
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io::Write,
+    process,
+};
+
+fn price_updates() -> Vec<PriceUpdate> {
+    let book = OrderBook::new(
+        vec![PriceLevel::new(Decimal::from(999), Decimal::from(2))],
+        vec![PriceLevel::new(Decimal::from(1001), Decimal::from(3))],
+    );
+
+    vec![
+        PriceUpdate::from_str("2017-11-01T09:42:23+00:00 KRAKEN BTC USD 1000.0 0.0009")
+            .unwrap()
+            .with_order_book(book),
+        PriceUpdate::from_str("2017-11-01T09:43:23+00:00 GDAX BTC USD 1001.0 0.0008").unwrap(),
+        PriceUpdate::from_str("2017-11-01T09:44:23+00:00 KRAKEN BTC USD 1000.0 0.00095").unwrap(),
+    ]
+}
+
+fn request() -> ExchangeRateRequest {
+    ExchangeRateRequest::new(
+        ExchangeType::GDAX,
+        Currency::USD,
+        ExchangeType::KRAKEN,
+        Currency::BTC,
+    )
+}
+
+fn write_log(updates: &[PriceUpdate]) -> Vec<u8> {
+    let mut log = PriceLog::new(vec![]);
+    for update in updates {
+        log.append(update).unwrap();
+    }
+
+    log.into_inner()
+}
+
+#[test]
+fn replays_as_of_a_timestamp() {
+    let log = write_log(&price_updates());
+    let as_of = Utc.ymd(2017, 11, 1).and_hms(9, 43, 30);
+
+    let mut rate_graph = RateGraph::default();
+    let report = rate_graph.replay(&log[..], Some(as_of)).unwrap();
+    assert_eq!(report.applied(), &price_updates()[..2]);
+
+    let expected = RateGraph::from(price_updates()[..2].to_vec());
+    assert_eq!(
+        rate_graph.full_path(&request()).unwrap(),
+        expected.full_path(&request()).unwrap()
+    );
+    assert_eq!(
+        rate_graph.quote(&request(), Decimal::from(100)).unwrap(),
+        expected.quote(&request(), Decimal::from(100)).unwrap()
+    );
+
+    let mut rate_graph = RateGraph::default();
+    let report = rate_graph.replay(&log[..], None).unwrap();
+    assert_eq!(report.applied(), &price_updates()[..]);
+}
+
+#[test]
+fn keeps_bid_and_ask() {
+    let updates = vec![
+        PriceUpdate::from_bid_ask(
+            Utc.ymd(2017, 11, 1).and_hms(9, 42, 23),
+            ExchangeType::GDAX,
+            Currency::BTC,
+            Currency::USD,
+            Decimal::from_str("6400.99").unwrap(),
+            Decimal::from_str("6401.01").unwrap(),
+        )
+        .with_last(Decimal::from_str("6401").unwrap()),
+        price_updates().remove(0),
+    ];
+    let log = write_log(&updates);
+
+    let mut rate_graph = RateGraph::default();
+    let report = rate_graph.replay(&log[..], None).unwrap();
+    assert_eq!(report.applied(), &updates[..]);
+    assert_eq!(
+        report.applied()[0].ask(),
+        Decimal::from_str("6401.01").unwrap()
+    );
+    assert_eq!(report.applied()[0].mid(), Decimal::from(6401));
+}
+
+#[test]
+fn ignores_truncated_final_record() {
+    let mut log = write_log(&price_updates()[..2]);
+    log.extend_from_slice(b"2017-11-01T09:44:23+00:00 KRAKEN BTC USD 1 0.0");
+
+    let mut rate_graph = RateGraph::default();
+    let report = rate_graph.replay(&log[..], None).unwrap();
+    assert_eq!(report.applied(), &price_updates()[..2]);
+
+    let mut log = write_log(&price_updates());
+    let header = log.iter().position(|byte| *byte == b'\n').unwrap() + 1;
+    log.splice(
+        header..header,
+        b"2017-11-01T09:44:23+00:00 KRAKEN\n".iter().cloned(),
+    );
+    assert_matches!(
+        RateGraph::default().replay(&log[..], None),
+        Err(LogError::InvalidRecord(2, _))
+    );
+}
+
+#[test]
+fn rejects_logs_of_other_versions() {
+    let log = write_log(&price_updates());
+    assert!(log.starts_with(format!("PRICE_LOG {}\n", LOG_VERSION).as_bytes()));
+
+    let headerless = "2017-11-01T09:42:23+00:00 KRAKEN BTC USD 1000.0 0.0009\n";
+    assert_matches!(
+        RateGraph::default().replay(headerless.as_bytes(), None),
+        Err(LogError::MissingHeader)
+    );
+    assert_matches!(
+        RateGraph::default().replay("PRICE_LOG 1\n".as_bytes(), None),
+        Err(LogError::UnsupportedVersion(ref version)) if version == "1"
+    );
+
+    let path = env::temp_dir().join(format!("exchange-rate-v1-{}.log", process::id()));
+    fs::write(&path, headerless).unwrap();
+    let opened = PriceLog::open(&path);
+    fs::remove_file(&path).unwrap();
+    assert!(opened.is_err());
+}
+
+#[test]
+fn repairs_truncated_log_files() {
+    let path = env::temp_dir().join(format!("exchange-rate-{}.log", process::id()));
+    fs::write(&path, write_log(&price_updates()[..2])).unwrap();
+
+    let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+    file.write_all(b"2017-11-01T09:44:23+00:00 KRAK").unwrap();
+
+    let mut log = PriceLog::open(&path).unwrap();
+    log.append(&price_updates()[2]).unwrap();
+
+    let mut rate_graph = RateGraph::default();
+    let report = rate_graph
+        .replay(&fs::read(&path).unwrap()[..], None)
+        .unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(report.applied(), &price_updates()[..]);
+}
+
+#[test]
+fn replays_every_change() {
+    let updates = price_updates().into_iter().map(LogRecord::Update);
+    let records: Vec<LogRecord> = updates
+        .chain(vec![
+            LogRecord::Suspend(ExchangeType::KRAKEN),
+            LogRecord::Fees(
+                FeeModel::default().with_trading_fee(ExchangeType::GDAX, Decimal::new(1, 2)),
+            ),
+            LogRecord::MaxAge(Some(Duration::minutes(10))),
+            LogRecord::Resume(ExchangeType::KRAKEN),
+            LogRecord::ExpireBefore(Utc.ymd(2017, 11, 1).and_hms(9, 43, 30)),
+            LogRecord::RemoveMarket(ExchangeType::GDAX, Currency::LTC, Currency::USD),
+        ])
+        .collect();
+
+    let mut log = PriceLog::new(vec![]);
+    log.append_records(&records).unwrap();
+    let log = log.into_inner();
+
+    let mut expected = RateGraph::default();
+    expected.apply(records);
+
+    let mut rate_graph = RateGraph::default();
+    rate_graph.replay(&log[..], None).unwrap();
+
+    assert_eq!(
+        rate_graph.fees().trading_fee(ExchangeType::GDAX),
+        Decimal::new(1, 2)
+    );
+    assert!(rate_graph.suspended_exchanges().is_empty());
+    assert_eq!(rate_graph.expired_markets(), expected.expired_markets());
+    assert_eq!(rate_graph.expired_markets().len(), 2);
+    assert_eq!(
+        rate_graph.full_path(&request()).unwrap(),
+        expected.full_path(&request()).unwrap()
+    );
+}
+
+#[test]
+fn ignores_changes_made_after_as_of() {
+    let records = vec![
+        LogRecord::Update(price_updates().remove(0)),
+        LogRecord::Update(
+            PriceUpdate::from_str("2017-11-01T10:42:23+00:00 GDAX BTC USD 1001.0 0.0008").unwrap(),
+        ),
+        LogRecord::Suspend(ExchangeType::KRAKEN),
+        LogRecord::Fees(
+            FeeModel::default().with_trading_fee(ExchangeType::KRAKEN, Decimal::new(1, 2)),
+        ),
+    ];
+
+    let mut log = PriceLog::new(vec![]);
+    log.append_records(&records).unwrap();
+    let log = log.into_inner();
+
+    let mut rate_graph = RateGraph::default();
+    let as_of = Utc.ymd(2017, 11, 1).and_hms(9, 42, 23);
+    let report = rate_graph.replay(&log[..], Some(as_of)).unwrap();
+
+    assert_eq!(report.applied(), &price_updates()[..1]);
+    assert!(rate_graph.suspended_exchanges().is_empty());
+    assert_eq!(
+        rate_graph.fees().trading_fee(ExchangeType::KRAKEN),
+        Decimal::from(0)
+    );
+
+    let request = ExchangeRateRequest::new(
+        ExchangeType::KRAKEN,
+        Currency::BTC,
+        ExchangeType::KRAKEN,
+        Currency::USD,
+    );
+    assert!(rate_graph.full_path(&request).is_ok());
+}
@@ -0,0 +1,122 @@
+use exchange_rate::exchange::RateGraphError;
+use exchange_rate::prelude::*;
+use matches::assert_matches;
+
+fn decimal(value: &str) -> Decimal {
+    Decimal::from_str(value).unwrap()
+}
+
+fn price_update(exchange: ExchangeType, book: OrderBook) -> PriceUpdate {
+    PriceUpdate::new(
+        Utc.ymd(2017, 11, 1).and_hms(9, 42, 23),
+        exchange,
+        Currency::BTC,
+        Currency::USD,
+        decimal("1000.0"),
+        decimal("0.0009"),
+    )
+    .with_order_book(book)
+}
+
+fn kraken_book() -> OrderBook {
+    OrderBook::new(
+        vec![
+            PriceLevel::new(decimal("900"), decimal("2")),
+            PriceLevel::new(decimal("1000"), decimal("1")),
+        ],
+        vec![
+            PriceLevel::new(decimal("2000"), decimal("5")),
+            PriceLevel::new(decimal("1000"), decimal("1")),
+        ],
+    )
+}
+
+fn request(source: Currency, destination: Currency) -> ExchangeRateRequest {
+    ExchangeRateRequest::new(
+        ExchangeType::KRAKEN,
+        source,
+        ExchangeType::KRAKEN,
+        destination,
+    )
+}
+
+#[test]
+fn walks_the_order_book() {
+    let rate_graph = RateGraph::from(vec![price_update(ExchangeType::KRAKEN, kraken_book())]);
+
+    let quote = rate_graph
+        .quote(&request(Currency::BTC, Currency::USD), 2.into())
+        .unwrap();
+    assert_eq!(quote.received(), 1900.into());
+    assert_eq!(quote.rate(), 950.into());
+    assert_eq!(quote.fills().len(), 1);
+
+    let quote = rate_graph
+        .quote(&request(Currency::USD, Currency::BTC), 3000.into())
+        .unwrap();
+    assert_eq!(quote.received(), 2.into());
+}
+
+#[test]
+fn rejects_amounts_deeper_than_the_book() {
+    let rate_graph = RateGraph::from(vec![price_update(ExchangeType::KRAKEN, kraken_book())]);
+
+    assert_matches!(
+        rate_graph.quote(&request(Currency::BTC, Currency::USD), 10.into()),
+        Err(RateGraphError::InsufficientLiquidity(_, _))
+    );
+    assert_matches!(
+        rate_graph.quote(&request(Currency::BTC, Currency::USD), 0.into()),
+        Err(RateGraphError::NonPositiveAmount(_))
+    );
+}
+
+#[test]
+fn splits_across_routes() {
+    let shallow = OrderBook::new(vec![PriceLevel::new(decimal("1000"), decimal("1"))], vec![]);
+    let deep = OrderBook::new(vec![PriceLevel::new(decimal("990"), decimal("10"))], vec![]);
+    let rate_graph = RateGraph::from(vec![
+        price_update(ExchangeType::KRAKEN, shallow),
+        price_update(ExchangeType::GDAX, deep),
+    ]);
+
+    let quote = rate_graph
+        .quote(&request(Currency::BTC, Currency::USD), 2.into())
+        .unwrap();
+    let fills = quote.fills();
+
+    assert_eq!(quote.received(), 1990.into());
+    assert_eq!(fills.len(), 2);
+    assert_eq!(fills[0].amount(), 1.into());
+    assert_eq!(fills[0].route().len(), 2);
+    assert_eq!(fills[1].amount(), 1.into());
+    assert_eq!(fills[1].received(), 990.into());
+}
+
+#[test]
+fn quotes_routes_of_many_hops() {
+    let currencies: Vec<Currency> = ["HOP1", "HOP2", "HOP3", "HOP4", "HOP5", "HOP6"]
+        .iter()
+        .map(|symbol| Currency::register(symbol))
+        .collect();
+    let updates = currencies
+        .windows(2)
+        .map(|pair| {
+            PriceUpdate::new(
+                Utc.ymd(2017, 11, 1).and_hms(9, 42, 23),
+                ExchangeType::KRAKEN,
+                pair[0],
+                pair[1],
+                decimal("2"),
+                decimal("0.5"),
+            )
+        })
+        .collect::<Vec<_>>();
+    let rate_graph = RateGraph::from(updates);
+
+    let quote = rate_graph
+        .quote(&request(currencies[0], currencies[5]), 3.into())
+        .unwrap();
+    assert_eq!(quote.received(), 96.into());
+    assert_eq!(quote.fills()[0].route().len(), 6);
+}
@@ -0,0 +1,81 @@
+use exchange_rate::exchange::{
+    discover_symbols, load_registry, ExchangeVertex, Registry, MAX_DISCOVERED_SYMBOLS,
+};
+use exchange_rate::prelude::*;
+use std::fs::read;
+
+#[test]
+fn interns_symbols_case_insensitively() {
+    let mut registry = Registry::new(&["USD"]);
+
+    assert_eq!(registry.intern("eth"), 1);
+    assert_eq!(registry.intern("ETH"), 1);
+    assert_eq!(registry.id("usd"), Some(0));
+    assert_eq!(registry.symbol(1), Some("ETH"));
+    assert_eq!(registry.resolve("DOGE"), None);
+
+    registry.set_discover(true);
+    assert_eq!(registry.resolve("DOGE"), Some(2));
+}
+
+#[test]
+fn caps_discovered_symbols() {
+    let mut registry = Registry::new(&["USD"]);
+    registry.set_discover(true);
+
+    for index in 0..MAX_DISCOVERED_SYMBOLS {
+        assert!(registry.resolve(&format!("COIN{}", index)).is_some());
+    }
+    assert_eq!(registry.resolve("DOGE"), None);
+    assert_eq!(registry.resolve("coin0"), Some(1));
+
+    // Symbols registered explicitly aren't discovered, so aren't capped.
+    assert_eq!(registry.intern("DOGE") as usize, MAX_DISCOVERED_SYMBOLS + 1);
+}
+
+#[test]
+fn keeps_built_in_symbols() {
+    assert_eq!(Currency::from_str("btc").unwrap(), Currency::BTC);
+    assert_eq!(
+        ExchangeType::from_str("Kraken").unwrap(),
+        ExchangeType::KRAKEN
+    );
+    assert_eq!(Currency::LTC.to_string(), "LTC");
+    assert_eq!(format!("{:?}", ExchangeType::GDAX), "GDAX");
+}
+
+#[test]
+fn loads_symbols_from_config() {
+    let file = &read("tests/inputs/easy/registry.txt").unwrap();
+    load_registry(&file[..]).unwrap();
+
+    let eth = Currency::from_str("ETH").unwrap();
+    let binance = ExchangeType::from_str("binance").unwrap();
+    assert_eq!(eth, Currency::register("eth"));
+    assert_eq!(binance.to_string(), "BINANCE");
+
+    assert!(load_registry("TOKEN XRP".as_bytes()).is_err());
+}
+
+#[test]
+fn discovers_symbols_from_the_stream() {
+    assert!(PriceUpdate::from_str("2017-11-01T09:42:23+00:00 BITSTAMP XRP USD 1.0 1.0").is_err());
+
+    discover_symbols(true);
+    let update = PriceUpdate::from_str("2017-11-01T09:42:23+00:00 BITSTAMP XRP USD 2.0 0.5");
+    let request = ExchangeRateRequest::from_str("EXCHANGE_RATE_REQUEST BITSTAMP XRP BITSTAMP USD");
+    discover_symbols(false);
+
+    let rate_graph = RateGraph::from(vec![update.unwrap()]);
+    let best_rate = rate_graph.full_path(&request.unwrap()).unwrap();
+    let bitstamp = ExchangeType::lookup("BITSTAMP").unwrap();
+
+    assert_eq!(
+        best_rate.vertices(),
+        vec![
+            ExchangeVertex::new(bitstamp, Currency::lookup("XRP").unwrap()),
+            ExchangeVertex::new(bitstamp, Currency::USD),
+        ]
+    );
+    assert_eq!(best_rate.rate(), 2.into());
+}
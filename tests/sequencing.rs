@@ -0,0 +1,73 @@
+use exchange_rate::exchange::UpdateRejection;
+use exchange_rate::prelude::*;
+
+fn price_update(minute: u32, source: Currency, factor: &str) -> PriceUpdate {
+    PriceUpdate::new(
+        Utc.ymd(2017, 11, 1).and_hms(9, minute, 0),
+        ExchangeType::KRAKEN,
+        source,
+        Currency::USD,
+        Decimal::from_str("1").unwrap(),
+        Decimal::from_str(factor).unwrap(),
+    )
+}
+
+fn request() -> ExchangeRateRequest {
+    ExchangeRateRequest::new(
+        ExchangeType::KRAKEN,
+        Currency::USD,
+        ExchangeType::KRAKEN,
+        Currency::BTC,
+    )
+}
+
+#[test]
+fn ignores_older_updates_of_a_market() {
+    let mut rate_graph = RateGraph::default();
+    let report = rate_graph.insert(vec![
+        price_update(10, Currency::BTC, "0.002"),
+        price_update(5, Currency::BTC, "0.001"),
+    ]);
+
+    assert_eq!(
+        report.applied(),
+        &[price_update(10, Currency::BTC, "0.002")]
+    );
+    assert_eq!(report.stale(), &[price_update(5, Currency::BTC, "0.001")]);
+
+    let best_rate = rate_graph.full_path(&request()).unwrap();
+    assert_eq!(best_rate.rate(), Decimal::from_str("0.002").unwrap());
+
+    // A newer update of the same market is still applied.
+    let report = rate_graph.insert(vec![price_update(15, Currency::BTC, "0.001")]);
+    assert_eq!(report.applied().len(), 1);
+
+    let best_rate = rate_graph.full_path(&request()).unwrap();
+    assert_eq!(best_rate.rate(), Decimal::from_str("0.001").unwrap());
+}
+
+#[test]
+fn rejects_invalid_updates() {
+    let mut rate_graph = RateGraph::default();
+    let report = rate_graph.insert(vec![
+        price_update(0, Currency::USD, "0.002"),
+        price_update(0, Currency::BTC, "0"),
+    ]);
+
+    assert!(report.applied().is_empty());
+    assert_eq!(
+        report
+            .rejected()
+            .iter()
+            .map(|(_, reason)| *reason)
+            .collect::<Vec<_>>(),
+        vec![
+            UpdateRejection::SameCurrency,
+            UpdateRejection::NonPositiveFactor
+        ]
+    );
+    assert_eq!(
+        UpdateRejection::NonPositiveFactor.to_string(),
+        "price update has a factor which isn't positive"
+    );
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "serde")]
+
+use exchange_rate::exchange::ExchangeVertex;
+use exchange_rate::prelude::*;
+
+#[test]
+fn round_trips_price_update() {
+    let update =
+        PriceUpdate::from_str("2017-11-01T09:42:23+00:00 KRAKEN BTC USD 1000.0 0.0009").unwrap();
+
+    let json = serde_json::to_string(&update).unwrap();
+    assert_eq!(serde_json::from_str::<PriceUpdate>(&json).unwrap(), update);
+}
+
+#[test]
+fn round_trips_exchange_rate_request() {
+    let request = ExchangeRateRequest::new(
+        ExchangeType::KRAKEN,
+        Currency::USD,
+        ExchangeType::GDAX,
+        Currency::LTC,
+    );
+
+    let json = serde_json::to_string(&request).unwrap();
+    assert_eq!(
+        json,
+        r#"{"source_exchange":"KRAKEN","source_currency":"USD","destination_exchange":"GDAX","destination_currency":"LTC"}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<ExchangeRateRequest>(&json).unwrap(),
+        request
+    );
+}
+
+#[test]
+fn serializes_resources_as_symbols() {
+    let vertex = ExchangeVertex::new(ExchangeType::GDAX, Currency::BTC);
+
+    assert_eq!(serde_json::to_string(&vertex).unwrap(), r#"["GDAX","BTC"]"#);
+    assert_eq!(
+        serde_json::from_str::<ExchangeVertex>(r#"["gdax","btc"]"#).unwrap(),
+        vertex
+    );
+    assert!(serde_json::from_str::<Currency>(r#""DOGE""#).is_err());
+    assert!(serde_json::from_str::<ExchangeType>(r#""NASDAQ""#).is_err());
+}
+
+#[test]
+fn round_trips_best_rate() {
+    let update =
+        PriceUpdate::from_str("2017-11-01T09:42:23+00:00 KRAKEN BTC USD 1000.0 0.0009").unwrap();
+    let request = ExchangeRateRequest::from_str("EXCHANGE_RATE_REQUEST KRAKEN USD KRAKEN BTC");
+    let best_rate = RateGraph::from(vec![update])
+        .full_path(&request.unwrap())
+        .unwrap();
+
+    let json = serde_json::to_string(&best_rate).unwrap();
+    assert_eq!(serde_json::from_str::<BestRate>(&json).unwrap(), best_rate);
+}
+
+#[test]
+fn serializes_price_updates_as_bid_and_ask() {
+    let update = PriceUpdate::from_bid_ask(
+        Utc.ymd(2017, 11, 1).and_hms(9, 42, 23),
+        ExchangeType::KRAKEN,
+        Currency::BTC,
+        Currency::USD,
+        Decimal::from_str("6400.99").unwrap(),
+        Decimal::from_str("6401.01").unwrap(),
+    );
+
+    let json = serde_json::to_string(&update).unwrap();
+    assert!(!json.contains("backward_factor"));
+    assert_eq!(serde_json::from_str::<PriceUpdate>(&json).unwrap(), update);
+
+    // The backward factor is always derived from the ask.
+    let quoted = |bid: &str, ask: &str| {
+        format!(
+            r#"{{"timestamp":"2017-11-01T09:42:23Z","exchange":"KRAKEN","source_currency":"BTC","destination_currency":"USD","bid":"{}","ask":"{}","backward_factor":"0.5"}}"#,
+            bid, ask
+        )
+    };
+    let update = serde_json::from_str::<PriceUpdate>(&quoted("999", "1000")).unwrap();
+    assert_eq!(
+        *update.backward_factor(),
+        Decimal::from_str("0.001").unwrap()
+    );
+
+    assert!(serde_json::from_str::<PriceUpdate>(&quoted("999", "0")).is_err());
+    assert!(serde_json::from_str::<PriceUpdate>(&quoted("1001", "1000")).is_err());
+}
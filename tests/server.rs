@@ -0,0 +1,122 @@
+#![cfg(feature = "server")]
+
+use exchange_rate::exchange::PriceLog;
+use exchange_rate::prelude::*;
+use exchange_rate::server::{Server, MAX_BODY_SIZE};
+use std::{
+    env, fs,
+    io::{Read, Write},
+    net::{SocketAddr, TcpStream},
+    process, thread,
+};
+
+/// Start a server on any free local port, answering requests in the background.
+fn start() -> SocketAddr {
+    let server = Server::bind("127.0.0.1:0", RateGraph::default()).unwrap();
+    let addr = server.local_addr();
+
+    thread::spawn(move || server.run());
+    addr
+}
+
+/// Send a request to a server, and returns the status code and body of its response.
+fn send(addr: SocketAddr, method: &str, target: &str, body: &str) -> (u16, serde_json::Value) {
+    let mut stream = TcpStream::connect(addr).unwrap();
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{}",
+        method,
+        target,
+        body.len(),
+        body
+    )
+    .unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+
+    let (head, body) = response.split_once("\r\n\r\n").unwrap();
+    let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+    (status, serde_json::from_str(body).unwrap())
+}
+
+fn price_updates() -> String {
+    let updates = vec![
+        PriceUpdate::from_str("2017-11-01T09:42:23+00:00 KRAKEN BTC USD 1000.0 0.0009").unwrap(),
+        PriceUpdate::from_str("2017-11-01T09:43:23+00:00 GDAX BTC USD 1001.0 0.0008").unwrap(),
+    ];
+
+    serde_json::to_string(&updates).unwrap()
+}
+
+#[test]
+fn serves_best_rates_of_posted_prices() {
+    let addr = start();
+
+    let (status, report) = send(addr, "POST", "/prices", &price_updates());
+    assert_eq!(status, 200);
+    assert_eq!(report["applied"].as_array().unwrap().len(), 2);
+
+    let (status, best_rate) = send(addr, "GET", "/rates?from=GDAX:USD&to=KRAKEN%3ABTC", "");
+    let best_rate: BestRate = serde_json::from_value(best_rate).unwrap();
+
+    assert_eq!(status, 200);
+    assert_eq!(best_rate.rate(), Decimal::from_str("0.0009").unwrap());
+    assert_eq!(best_rate.hops().len(), 2);
+}
+
+#[test]
+fn reports_errors_as_json() {
+    let addr = start();
+
+    let (status, body) = send(addr, "POST", "/prices", "[{");
+    assert_eq!(status, 400);
+    assert!(body["error"].is_string());
+
+    let crossed = r#"[{"timestamp":"2017-11-01T09:42:23Z","exchange":"KRAKEN","source_currency":"BTC","destination_currency":"USD","bid":"1001","ask":"1000"}]"#;
+    let (status, _) = send(addr, "POST", "/prices", crossed);
+    assert_eq!(status, 400);
+
+    let (status, _) = send(addr, "GET", "/rates?from=GDAX:DOGE&to=KRAKEN:BTC", "");
+    assert_eq!(status, 400);
+
+    let (status, _) = send(addr, "GET", "/rates?from=GDAX:USD&to=KRAKEN:BTC", "");
+    assert_eq!(status, 404);
+
+    let (status, _) = send(addr, "DELETE", "/rates", "");
+    assert_eq!(status, 405);
+}
+
+#[test]
+fn rejects_oversized_bodies() {
+    let addr = start();
+
+    let body = " ".repeat(MAX_BODY_SIZE as usize + 1);
+    let (status, reply) = send(addr, "POST", "/prices", &body);
+    assert_eq!(status, 413);
+    assert!(reply["error"].is_string());
+
+    let (status, _) = send(addr, "POST", "/prices", &price_updates());
+    assert_eq!(status, 200);
+}
+
+#[test]
+fn logs_posted_prices_before_applying_them() {
+    let path = env::temp_dir().join(format!("exchange-rate-server-{}.log", process::id()));
+    let server = Server::bind("127.0.0.1:0", RateGraph::default())
+        .unwrap()
+        .with_log(PriceLog::open(&path).unwrap());
+    let addr = server.local_addr();
+    thread::spawn(move || server.run());
+
+    let (status, _) = send(addr, "POST", "/prices", &price_updates());
+    assert_eq!(status, 200);
+
+    let mut rate_graph = RateGraph::default();
+    let report = rate_graph
+        .replay(&fs::read(&path).unwrap()[..], None)
+        .unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(report.applied().len(), 2);
+}
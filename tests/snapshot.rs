@@ -0,0 +1,126 @@
+use chrono::Duration;
+use exchange_rate::exchange::{FeeModel, SnapshotError};
+use exchange_rate::prelude::*;
+use matches::assert_matches;
+
+fn rate_graph() -> RateGraph {
+    let fees = FeeModel::default()
+        .with_trading_fee(ExchangeType::KRAKEN, Decimal::from_str("0.01").unwrap())
+        .with_withdrawal_fee(Currency::USD, Decimal::from_str("5").unwrap())
+        .with_transfer_factor(
+            ExchangeType::GDAX,
+            ExchangeType::KRAKEN,
+            Decimal::from_str("0.5").unwrap(),
+        );
+
+    let book = OrderBook::new(
+        vec![PriceLevel::new(Decimal::from(999), Decimal::from(2))],
+        vec![PriceLevel::new(Decimal::from(1001), Decimal::from(3))],
+    );
+
+    let mut rate_graph = RateGraph::with_fees(fees);
+    rate_graph.insert(vec![
+        PriceUpdate::from_str("2017-11-01T09:42:23+00:00 KRAKEN BTC USD 1000.0 0.0009")
+            .unwrap()
+            .with_order_book(book),
+        PriceUpdate::from_str("2017-11-01T09:43:23+00:00 GDAX BTC USD 1001.0 0.0008")
+            .unwrap()
+            .with_last(Decimal::from(1002)),
+        PriceUpdate::from_str("2017-11-01T09:44:23+00:00 GDAX LTC USD 100.0 0.001").unwrap(),
+    ]);
+    rate_graph.suspend_exchange(ExchangeType::GDAX);
+    rate_graph
+}
+
+fn save(rate_graph: &RateGraph) -> String {
+    let mut snapshot = vec![];
+    rate_graph.save_snapshot(&mut snapshot).unwrap();
+    String::from_utf8(snapshot).unwrap()
+}
+
+#[test]
+fn round_trips_snapshots() {
+    let mut rate_graph = rate_graph();
+    let snapshot = save(&rate_graph);
+    let mut restored = RateGraph::load_snapshot(snapshot.as_bytes()).unwrap();
+
+    assert_eq!(save(&restored), snapshot);
+    assert_eq!(
+        restored.suspended_exchanges(),
+        rate_graph.suspended_exchanges()
+    );
+
+    rate_graph.resume_exchange(ExchangeType::GDAX);
+    restored.resume_exchange(ExchangeType::GDAX);
+
+    let request = ExchangeRateRequest::new(
+        ExchangeType::GDAX,
+        Currency::USD,
+        ExchangeType::KRAKEN,
+        Currency::BTC,
+    );
+
+    assert_eq!(
+        restored.full_path(&request).unwrap(),
+        rate_graph.full_path(&request).unwrap()
+    );
+    assert_eq!(
+        restored.quote(&request, Decimal::from(1000)).unwrap(),
+        rate_graph.quote(&request, Decimal::from(1000)).unwrap()
+    );
+}
+
+#[test]
+fn restores_expired_markets() {
+    let mut rate_graph = RateGraph::default();
+    rate_graph.set_max_age(Some(Duration::minutes(1)));
+    rate_graph.insert(vec![
+        PriceUpdate::from_str("2017-11-01T09:42:23+00:00 KRAKEN BTC USD 1000.0 0.0009").unwrap(),
+        PriceUpdate::from_str("2017-11-01T09:44:23+00:00 GDAX BTC USD 1001.0 0.0008").unwrap(),
+    ]);
+
+    let mut restored = RateGraph::load_snapshot(save(&rate_graph).as_bytes()).unwrap();
+    assert_eq!(restored.expired_markets(), rate_graph.expired_markets());
+
+    // Updates older than the expired market are still ignored, while fresh
+    // ones are only applied until they expire in turn.
+    let older = PriceUpdate::from_str("2017-11-01T09:41:23+00:00 KRAKEN BTC USD 999.0 0.001");
+    assert_eq!(restored.insert(vec![older.unwrap()]).stale().len(), 1);
+
+    let later = PriceUpdate::from_str("2017-11-01T09:46:23+00:00 KRAKEN LTC USD 100.0 0.01");
+    restored.insert(vec![later.unwrap()]);
+    assert_eq!(restored.expired_markets().len(), 4);
+}
+
+#[test]
+fn rejects_incompatible_snapshots() {
+    assert_matches!(
+        RateGraph::load_snapshot("RATE_GRAPH_SNAPSHOT 1\n".as_bytes()),
+        Err(SnapshotError::UnsupportedVersion(ref version)) if version == "1"
+    );
+    assert_matches!(
+        RateGraph::load_snapshot("".as_bytes()),
+        Err(SnapshotError::MissingHeader)
+    );
+    assert_matches!(
+        RateGraph::load_snapshot("RATE_GRAPH_SNAPSHOT 2\nPRICE KRAKEN\n".as_bytes()),
+        Err(SnapshotError::InvalidEntry(2, _))
+    );
+}
+
+#[test]
+fn keeps_bid_and_ask() {
+    let update = PriceUpdate::from_bid_ask(
+        Utc.ymd(2017, 11, 1).and_hms(9, 42, 23),
+        ExchangeType::GDAX,
+        Currency::BTC,
+        Currency::USD,
+        Decimal::from_str("6400.99").unwrap(),
+        Decimal::from_str("6401.01").unwrap(),
+    );
+    let snapshot = save(&RateGraph::from(vec![update]));
+    assert!(snapshot.contains("PRICE 2017-11-01T09:42:23+00:00 GDAX BTC USD 6400.99 6401.01 "));
+
+    let restored = RateGraph::load_snapshot(snapshot.as_bytes()).unwrap();
+    assert_eq!(save(&restored), snapshot);
+}
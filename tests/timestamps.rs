@@ -0,0 +1,73 @@
+use exchange_rate::prelude::*;
+
+fn timestamp(data: &str) -> DateTime<Utc> {
+    let update = format!("{} KRAKEN BTC USD 1000.0 0.0009", data);
+    *PriceUpdate::from_str(&update).unwrap().timestamp()
+}
+
+fn price_update(timestamp: &str, factor: &str) -> PriceUpdate {
+    let update = format!("{} KRAKEN BTC USD 1 {}", timestamp, factor);
+    PriceUpdate::from_str(&update).unwrap()
+}
+
+#[test]
+fn normalizes_timestamps_to_utc() {
+    let expected = Utc.ymd(2017, 11, 1).and_hms(9, 42, 23);
+
+    for data in &[
+        "2017-11-01T09:42:23+00:00",
+        "2017-11-01T09:42:23Z",
+        "2017-11-01T10:42:23+01:00",
+        "2017-11-01T04:42:23.000-05:00",
+        "2017-11-01T09:42:23+0000",
+        "1509529343",
+        "1509529343000",
+    ] {
+        assert_eq!(timestamp(data), expected, "{}", data);
+    }
+
+    let fractional = timestamp("2017-11-01T09:42:23.25Z");
+    assert_eq!(
+        fractional,
+        Utc.ymd(2017, 11, 1).and_hms_milli(9, 42, 23, 250)
+    );
+    assert_eq!(timestamp("1509529343250"), fractional);
+
+    // Fractional seconds survive a round trip through the update format.
+    let update = price_update("2017-11-01T09:42:23.25Z", "0.0009");
+    assert_eq!(
+        update.to_string(),
+        "2017-11-01T09:42:23.250+00:00 KRAKEN BTC USD 1 0.0009"
+    );
+    assert_eq!(PriceUpdate::from_str(&update.to_string()).unwrap(), update);
+
+    assert!(PriceUpdate::from_str("2017-11-01 KRAKEN BTC USD 1 0.0009").is_err());
+}
+
+#[test]
+fn compares_freshness_across_time_zones() {
+    let mut rate_graph = RateGraph::default();
+    let report = rate_graph.insert(vec![
+        price_update("2017-11-01T09:00:00+00:00", "0.001"),
+        // Earlier than the previous update, despite its later local time.
+        price_update("2017-11-01T10:00:00+02:00", "0.002"),
+        // Later than the first update, despite its earlier local time.
+        price_update("2017-11-01T04:30:00-05:00", "0.003"),
+    ]);
+
+    assert_eq!(report.applied().len(), 2);
+    assert_eq!(report.stale().len(), 1);
+    assert_eq!(
+        report.stale()[0].timestamp(),
+        &Utc.ymd(2017, 11, 1).and_hms(8, 0, 0)
+    );
+
+    let request = ExchangeRateRequest::new(
+        ExchangeType::KRAKEN,
+        Currency::USD,
+        ExchangeType::KRAKEN,
+        Currency::BTC,
+    );
+    let best_rate = rate_graph.full_path(&request).unwrap();
+    assert_eq!(best_rate.rate(), Decimal::from_str("0.003").unwrap());
+}
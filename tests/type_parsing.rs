@@ -8,7 +8,7 @@ fn parse_price_update() {
 
     let result = vec![
         PriceUpdate::new(
-            NaiveDate::from_ymd(2017, 11, 1).and_hms(9, 42, 23),
+            Utc.ymd(2017, 11, 1).and_hms(9, 42, 23),
             ExchangeType::KRAKEN,
             Currency::BTC,
             Currency::USD,
@@ -16,7 +16,7 @@ fn parse_price_update() {
             Decimal::from_str("0.0009").unwrap(),
         ),
         PriceUpdate::new(
-            NaiveDate::from_ymd(2017, 11, 1).and_hms(9, 43, 23),
+            Utc.ymd(2017, 11, 1).and_hms(9, 43, 23),
             ExchangeType::GDAX,
             Currency::BTC,
             Currency::USD,